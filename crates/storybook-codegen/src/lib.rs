@@ -0,0 +1,206 @@
+use serde_json::{json, Value};
+use std::io;
+use std::path::{Path, PathBuf};
+use storybook::template::{js_string_literal, js_text_literal, StoryTemplate, TemplateArgType};
+use storybook::{ArgType, ControlType, StoryRegistration};
+
+/// Write a `.stories.js` file for every registered story into `output_dir`, returning the written paths
+///
+/// Files are rendered from the same template as the ones `#[derive(Story)]` writes while
+/// compiling, so derived stories get exactly the same file, and they can be regenerated from a
+/// build tool or CI step without a full rebuild. Stories registered at runtime with
+/// `meta::StoryMetaBuilder` get a file built from their registration.
+///
+/// ```ignore
+/// // src/bin/generate-stories.rs
+/// fn main() -> std::io::Result<()> {
+///     example::register_all_stories();
+///     storybook_codegen::generate_all_story_files(std::path::Path::new("storybook/stories"))?;
+///     Ok(())
+/// }
+/// ```
+pub fn generate_all_story_files(output_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(output_dir)?;
+
+    storybook::story_registrations()
+        .iter()
+        .map(|story| {
+            let output_file = output_dir.join(format!("{}.stories.js", story.name));
+            std::fs::write(&output_file, story_js(story))?;
+            Ok(output_file)
+        })
+        .collect()
+}
+
+// The `.stories.js` file of a registered story, from the template its derive built if it has one
+fn story_js(story: &StoryRegistration) -> String {
+    (story.story_template)().unwrap_or_else(|| registration_template(story)).render()
+}
+
+// Template of a story that had no file written while compiling, from what its registration holds
+fn registration_template(story: &StoryRegistration) -> StoryTemplate {
+    let mut template = StoryTemplate::new(story.name);
+    template.title = story.title.clone();
+    template.component_id = story.component_id.map(str::to_string);
+    template.decorators = story.decorators.iter().map(|decorator| decorator.to_string()).collect();
+    template.screenshot = story.screenshot;
+    template.viewport = story.default_viewport.map(str::to_string);
+    template.portal_target = story.portal_target.map(|portal_target| portal_target.trim_start_matches('#').to_string());
+    template.export_name = story.primary_variant_name.to_string();
+    template.arg_types = (story.args)().iter().map(|arg| template_arg_type(story, arg)).collect();
+
+    if let Some(expanded) = story.controls_expanded_param() {
+        template.parameters.push(format!("controls: {{ expanded: {} }}", expanded));
+    }
+    if let Some(component_docs) = story.component_docs {
+        template.parameters.push(format!("docs: {{ description: {{ component: {} }} }}", js_text_literal(component_docs)));
+    }
+    template
+}
+
+// One entry of `argTypes`, with the control and extras `#[derive(Story)]` gives the same settings
+fn template_arg_type(story: &StoryRegistration, arg: &ArgType) -> TemplateArgType {
+    let mut extra = serde_json::Map::new();
+    let control = match arg.control {
+        // Switches are boolean controls whose values are mapped to on/off
+        ControlType::Switch => {
+            extra.insert("mapping".to_string(), json!({ "true": "on", "false": "off" }));
+            json!({ "type": "boolean" })
+        }
+        ControlType::Object => json!({ "type": "object" }),
+        ControlType::Inline => {
+            extra.insert("inlinePreview".to_string(), Value::Bool(true));
+            json!({ "type": "text" })
+        }
+        ControlType::Custom => match &arg.custom_control {
+            Some(component) => json!({ "type": "custom", "component": component }),
+            None => {
+                extra.insert("customControl".to_string(), Value::Bool(true));
+                json!({ "type": "object" })
+            }
+        },
+        ControlType::Text if arg.min_length.is_some() => json!({ "type": "text", "minLength": arg.min_length }),
+        // Percentages are edited as 0-100 and divided by 100 before rendering
+        ControlType::Number | ControlType::Range if arg.is_percentage() => {
+            json!({ "type": arg.control, "min": 0, "max": 100, "step": 1 })
+        }
+        ControlType::Number | ControlType::Range if arg.min.is_some() || arg.max.is_some() => {
+            let mut control = serde_json::Map::new();
+            control.insert("type".to_string(), json!(arg.control));
+            if let Some(min) = arg.min {
+                control.insert("min".to_string(), json!(min));
+            }
+            if let Some(max) = arg.max {
+                control.insert("max".to_string(), json!(max));
+            }
+            Value::Object(control)
+        }
+        ControlType::Select if arg.allow_custom_value => {
+            extra.insert("allowCustomValue".to_string(), Value::Bool(true));
+            json!({ "type": "text" })
+        }
+        ControlType::InlineRadio => {
+            let labels: serde_json::Map<String, Value> = arg
+                .options
+                .iter()
+                .flatten()
+                .zip(arg.radio_icons.iter().flatten())
+                .map(|(option, icon)| (option.clone(), json!(icon)))
+                .collect();
+            json!({ "type": "inline-radio", "labels": labels })
+        }
+        _ => json!(arg.control),
+    };
+
+    if let Some(options) = &arg.options {
+        extra.insert("options".to_string(), json!(options));
+    }
+    let mut table = serde_json::Map::new();
+    if arg.required {
+        table.insert("category".to_string(), json!("required"));
+    }
+    if let Some(type_detail) = arg.type_detail() {
+        table.insert("type".to_string(), json!({ "detail": type_detail }));
+    }
+    if !table.is_empty() {
+        extra.insert("table".to_string(), Value::Object(table));
+    }
+    if let Some((field, ratio)) = &arg.linked_field {
+        extra.insert("linkedTo".to_string(), json!({ "field": field, "ratio": ratio }));
+    }
+    if let Some(number_format) = &arg.number_format {
+        extra.insert("numberFormat".to_string(), json!({ "style": number_format, "currency": arg.number_currency }));
+    }
+
+    extra.insert("control".to_string(), control);
+    story.hide_arg_table_columns(&mut extra);
+    let control = extra.remove("control").unwrap_or(Value::Null);
+
+    TemplateArgType {
+        name: arg.name.clone(),
+        control: control.to_string(),
+        description: js_string_literal(arg.description.as_deref().unwrap_or(&arg.name)),
+        options: None,
+        table: Vec::new(),
+        extra: extra.iter().map(|(key, value)| format!("{}: {}", key, value)).collect(),
+        default_value: arg.default_json().map(|default| arg.to_display_value(default).to_string()),
+        percentage: arg.is_percentage(),
+        dependent_options: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dominator::{html, Dom};
+    use serde::Deserialize;
+    use storybook::meta::StoryMetaBuilder;
+    use storybook::{ArgTypeBuilder, Story, StoryDerive};
+
+    #[derive(StoryDerive, Deserialize)]
+    #[story(stories_dir = "../../target/codegen-test-stories", width = "320px")]
+    pub struct CodegenBadge {
+        #[story(control = "color", default = "'#007bff'")]
+        pub color: String,
+        #[story(default = "3", min = "0", max = "9")]
+        pub count: u32,
+    }
+
+    impl Story for CodegenBadge {
+        fn to_story(self) -> Dom {
+            html!("span", { .text(&format!("{} {}", self.color, self.count)) })
+        }
+    }
+
+    #[test]
+    fn generates_the_same_file_as_the_derive() {
+        storybook::register_story::<CodegenBadge>();
+
+        let output_dir = std::env::temp_dir().join("storybook-codegen-derived");
+        let written = generate_all_story_files(&output_dir).unwrap();
+        let generated_file = output_dir.join("CodegenBadge.stories.js");
+        assert!(written.contains(&generated_file));
+
+        let derived_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/codegen-test-stories/CodegenBadge.stories.js");
+        assert_eq!(std::fs::read_to_string(generated_file).unwrap(), std::fs::read_to_string(derived_file).unwrap());
+    }
+
+    #[test]
+    fn generates_files_for_runtime_stories() {
+        StoryMetaBuilder::new("CodegenRuntimeBadge")
+            .arg(ArgTypeBuilder::new("label").control(ControlType::Text).default("'New'").build().unwrap())
+            .description("A badge registered at runtime")
+            .render(|_| html!("span", {}))
+            .register()
+            .unwrap();
+
+        let output_dir = std::env::temp_dir().join("storybook-codegen-runtime");
+        generate_all_story_files(&output_dir).unwrap();
+        let js = std::fs::read_to_string(output_dir.join("CodegenRuntimeBadge.stories.js")).unwrap();
+
+        assert!(js.contains("title: 'Components/CodegenRuntimeBadge'"));
+        assert!(js.contains(storybook::template::DEFAULT_WASM_MODULE));
+        assert!(js.contains("label: {\n      control: \"text\""));
+        assert!(js.contains("export const Default"));
+    }
+}
@@ -0,0 +1,4 @@
+fn main() {
+    // The derive reads STORYBOOK_VERSION while expanding, so stories are regenerated when it changes
+    println!("cargo:rerun-if-env-changed=STORYBOOK_VERSION");
+}
@@ -0,0 +1,75 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE_NAME: &str = "storybook.toml";
+
+// Settings read from a `storybook.toml` config file
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct StorybookConfig {
+    #[serde(default)]
+    pub stories: StoriesConfig,
+}
+
+// The `[stories]` table of `storybook.toml`
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct StoriesConfig {
+    pub output_dir: Option<String>,
+    pub csf_version: Option<u8>,
+    pub default_layout: Option<String>,
+    pub error_boundary: Option<bool>,
+}
+
+// A loaded config along with the file it came from
+pub struct LoadedConfig {
+    pub path: PathBuf,
+    pub config: StorybookConfig,
+}
+
+impl LoadedConfig {
+    // Directory containing the config file, which relative paths are resolved against
+    pub fn dir(&self) -> &Path {
+        self.path.parent().unwrap_or_else(|| Path::new("."))
+    }
+}
+
+// Find `storybook.toml` in CARGO_MANIFEST_DIR or the nearest ancestor (e.g. the workspace root)
+pub fn find_config_file() -> Option<PathBuf> {
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").ok()?);
+    manifest_dir
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+// Load and validate `storybook.toml`, returning `None` if there isn't one
+pub fn load_config() -> Result<Option<LoadedConfig>, String> {
+    let path = match find_config_file() {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+    let config: StorybookConfig = toml::from_str(&content)
+        .map_err(|err| format!("invalid {}: {}", path.display(), err))?;
+
+    if let Some(csf_version) = config.stories.csf_version {
+        if csf_version != 2 && csf_version != 3 {
+            return Err(format!("invalid {}: csf_version must be 2 or 3, found {}", path.display(), csf_version));
+        }
+    }
+
+    if let Some(layout) = &config.stories.default_layout {
+        if !["centered", "padded", "fullscreen"].contains(&layout.as_str()) {
+            return Err(format!(
+                "invalid {}: default_layout must be one of centered, padded or fullscreen, found '{}'",
+                path.display(),
+                layout
+            ));
+        }
+    }
+
+    Ok(Some(LoadedConfig { path, config }))
+}
@@ -1,7 +1,13 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
 use syn::{parse_macro_input, DeriveInput, Data, Fields};
 
+mod config;
+
+use config::LoadedConfig;
+use storybook_template::{is_js_identifier, js_string_literal, js_template_literal, js_text_literal, StoryTemplate, TemplateArgType, DEFAULT_WASM_MODULE};
+
 // Helper to extract dominator_crate attribute from the struct
 // Returns the crate path as a string, defaults to "dominator"
 fn get_dominator_crate_attr(input: &DeriveInput) -> String {
@@ -19,14 +25,93 @@ fn get_dominator_crate_attr(input: &DeriveInput) -> String {
     "dominator".to_string()
 }
 
+// Field-level `#[story(...)]` settings
+#[derive(Default)]
+struct StoryFieldAttrs {
+    control_type: Option<String>,
+    default_value: Option<String>,
+    // `default_rust` expression, checked against the field's type and serialized for the JS default
+    default_rust: Option<syn::Expr>,
+    from_type: Option<syn::Type>,
+    lorem_count: Option<usize>,
+    skip: bool,
+    true_label: Option<String>,
+    false_label: Option<String>,
+    // Kept as a literal so a wrong number of labels can be reported at the attribute
+    bool_labels: Option<syn::LitStr>,
+    min: Option<f64>,
+    max: Option<f64>,
+    i18n_key: Option<String>,
+    // Help shown on the arg's type in the docs table, above the translation key
+    tooltip: Option<String>,
+    option_groups: Option<Vec<(String, Vec<String>)>>,
+    preview_fn: Option<syn::Path>,
+    renderer: Option<syn::Path>,
+    options_const: Option<syn::Path>,
+    // Function returning the select's options, called when the story is registered
+    options_fn: Option<syn::Path>,
+    rows: Option<usize>,
+    min_length: Option<usize>,
+    // Number field kept at `aspect_ratio` to this one, as `(field, ratio)`
+    linked_to: Option<String>,
+    aspect_ratio: Option<f64>,
+    // `Intl.NumberFormat` style and currency code for number controls
+    number_format: Option<String>,
+    // How a number control shows its value, e.g. `percentage` for 0-100
+    display_format: Option<String>,
+    currency: Option<String>,
+    // Kept as a literal so invalid values can be reported at the attribute
+    resize: Option<syn::LitStr>,
+    // `argtype_if` overrides as `(feature, attributes)`, applied when the feature is enabled
+    conditional_attrs: Vec<(String, String)>,
+    depends_on: Vec<String>,
+    dependent_options: Option<syn::Path>,
+    // Select whose value may also be typed in, rendered as a combobox
+    allow_custom_value: bool,
+    // Labels of an inline radio's options, in option order
+    radio_icons: Option<Vec<String>>,
+    // How a color control's value is held, e.g. `rgba_bytes` for `[u8; 4]`
+    color_type: Option<syn::LitStr>,
+    // Options of a select over an enum from another crate, parsed back with its `FromStr` impl
+    external_options: Option<Vec<String>>,
+}
+
+impl StoryFieldAttrs {
+    // Labels for a `bool` field rendered as a select, defaulting to "true"/"false"
+    fn bool_select_labels(&self, field_ty: &syn::Type) -> Option<(String, String)> {
+        let ty_string = quote!(#field_ty).to_string();
+        let is_bool = ty_string == "bool" || ty_string == "Option < bool >";
+
+        if is_bool && self.control_type.as_deref() == Some("select") {
+            Some((
+                self.true_label.clone().unwrap_or_else(|| "true".to_string()),
+                self.false_label.clone().unwrap_or_else(|| "false".to_string()),
+            ))
+        } else {
+            None
+        }
+    }
+
+    // Whether the field is a `[u8; 4]` edited as an `rgba(...)` color string
+    fn is_rgba_bytes(&self) -> bool {
+        self.control_type.as_deref() == Some("color")
+            && self.color_type.as_ref().is_some_and(|color_type| color_type.value() == "rgba_bytes")
+    }
+
+    // Whether the field is a select or inline radio over a `StorySelect` enum, rather than bool labels or a const array
+    fn is_enum_select(&self, field_ty: &syn::Type) -> bool {
+        matches!(self.control_type.as_deref(), Some("select" | "inline-radio"))
+            && self.options_const.is_none()
+            && self.options_fn.is_none()
+            && self.bool_select_labels(field_ty).is_none()
+            && self.dependent_options.is_none()
+            && self.external_options.is_none()
+    }
+}
+
 // Helper to extract story attributes from a field
-// Returns: (control_type, default_value, from_type, lorem_word_count, skip)
-fn get_story_attrs(field: &syn::Field) -> (Option<String>, Option<String>, Option<syn::Type>, Option<usize>, bool) {
-    let mut control_type = None;
-    let mut default_value = None;
-    let mut from_type = None;
-    let mut lorem_count = None;
-    let mut skip = false;
+fn get_story_attrs(field: &syn::Field) -> StoryFieldAttrs {
+    let mut field_attrs = StoryFieldAttrs::default();
 
     for attr in &field.attrs {
         if attr.path().is_ident("story") {
@@ -35,19 +120,25 @@ fn get_story_attrs(field: &syn::Field) -> (Option<String>, Option<String>, Optio
                 if meta.path.is_ident("control") {
                     if let Ok(value) = meta.value() {
                         if let Ok(lit_str) = value.parse::<syn::LitStr>() {
-                            control_type = Some(lit_str.value());
+                            field_attrs.control_type = Some(lit_str.value());
                         }
                     }
                 } else if meta.path.is_ident("default") {
                     if let Ok(value) = meta.value() {
                         if let Ok(lit_str) = value.parse::<syn::LitStr>() {
-                            default_value = Some(lit_str.value());
+                            field_attrs.default_value = Some(lit_str.value());
+                        }
+                    }
+                } else if meta.path.is_ident("default_rust") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.default_rust = lit_str.parse::<syn::Expr>().ok();
                         }
                     }
                 } else if meta.path.is_ident("from") {
                     if let Ok(value) = meta.value() {
                         if let Ok(lit_str) = value.parse::<syn::LitStr>() {
-                            from_type =
+                            field_attrs.from_type =
                                 Some(syn::parse_str(&lit_str.value()).expect("Invalid type for from"));
                         }
                     }
@@ -56,191 +147,1734 @@ fn get_story_attrs(field: &syn::Field) -> (Option<String>, Option<String>, Optio
                     if let Ok(value) = meta.value() {
                         if let Ok(lit_str) = value.parse::<syn::LitStr>() {
                             if let Ok(count) = lit_str.value().parse::<usize>() {
-                                lorem_count = Some(count);
+                                field_attrs.lorem_count = Some(count);
                             }
                         }
                     } else {
                         // No value specified, use default of 8
-                        lorem_count = Some(8);
+                        field_attrs.lorem_count = Some(8);
                     }
                 } else if meta.path.is_ident("skip") {
-                    skip = true;
+                    field_attrs.skip = true;
+                } else if meta.path.is_ident("true_label") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.true_label = Some(lit_str.value());
+                        }
+                    }
+                } else if meta.path.is_ident("false_label") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.false_label = Some(lit_str.value());
+                        }
+                    }
+                } else if meta.path.is_ident("argtype_if") {
+                    // e.g. `argtype_if(feature = "extended", then = "control = 'select', from = 'Mode'")`
+                    let mut feature = None;
+                    let mut then = None;
+                    let _ = meta.parse_nested_meta(|inner| {
+                        let lit_str = inner.value()?.parse::<syn::LitStr>()?;
+                        if inner.path.is_ident("feature") {
+                            feature = Some(lit_str.value());
+                        } else if inner.path.is_ident("then") {
+                            then = Some(lit_str.value());
+                        }
+                        Ok(())
+                    });
+                    if let (Some(feature), Some(then)) = (feature, then) {
+                        field_attrs.conditional_attrs.push((feature, then));
+                    }
+                } else if meta.path.is_ident("bool_labels") {
+                    // Shorthand for `true_label` and `false_label`, e.g. `bool_labels = "Yes,No"`
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            if let Ok((true_label, false_label)) = split_bool_labels(&lit_str) {
+                                field_attrs.true_label = Some(true_label);
+                                field_attrs.false_label = Some(false_label);
+                            }
+                            field_attrs.bool_labels = Some(lit_str);
+                        }
+                    }
+                } else if meta.path.is_ident("options_const") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.options_const = lit_str.parse::<syn::Path>().ok();
+                        }
+                    }
+                } else if meta.path.is_ident("options_fn") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.options_fn = lit_str.parse::<syn::Path>().ok();
+                        }
+                    }
+                } else if meta.path.is_ident("depends_on") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.depends_on = lit_str
+                                .value()
+                                .split(',')
+                                .map(|field| field.trim().to_string())
+                                .filter(|field| !field.is_empty())
+                                .collect();
+                        }
+                    }
+                } else if meta.path.is_ident("dependent_options") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.dependent_options = lit_str.parse::<syn::Path>().ok();
+                        }
+                    }
+                } else if meta.path.is_ident("preview_fn") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.preview_fn = lit_str.parse::<syn::Path>().ok();
+                        }
+                    }
+                } else if meta.path.is_ident("renderer") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.renderer = lit_str.parse::<syn::Path>().ok();
+                        }
+                    }
+                } else if meta.path.is_ident("rows") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.rows = lit_str.value().parse::<usize>().ok();
+                        }
+                    }
+                } else if meta.path.is_ident("linked_to") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.linked_to = Some(lit_str.value().trim().to_string());
+                        }
+                    }
+                } else if meta.path.is_ident("aspect_ratio") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.aspect_ratio = parse_aspect_ratio(&lit_str.value());
+                        }
+                    }
+                } else if meta.path.is_ident("format") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.number_format = Some(lit_str.value());
+                        }
+                    }
+                } else if meta.path.is_ident("display_format") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.display_format = Some(lit_str.value());
+                        }
+                    }
+                } else if meta.path.is_ident("currency") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.currency = Some(lit_str.value().trim().to_uppercase());
+                        }
+                    }
+                } else if meta.path.is_ident("icons") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.radio_icons = Some(lit_str.value().split(',').map(|icon| icon.trim().to_string()).collect());
+                        }
+                    }
+                } else if meta.path.is_ident("external_options") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.external_options = Some(
+                                lit_str
+                                    .value()
+                                    .split(',')
+                                    .map(|option| option.trim().to_string())
+                                    .filter(|option| !option.is_empty())
+                                    .collect(),
+                            );
+                        }
+                    }
+                } else if meta.path.is_ident("color_type") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.color_type = Some(lit_str);
+                        }
+                    }
+                } else if meta.path.is_ident("allow_custom_value") {
+                    if let Ok(value) = meta.value() {
+                        // Accepted as `true` or `"true"`
+                        match value.parse::<syn::Lit>() {
+                            Ok(syn::Lit::Bool(lit_bool)) => field_attrs.allow_custom_value = lit_bool.value,
+                            Ok(syn::Lit::Str(lit_str)) => field_attrs.allow_custom_value = lit_str.value() == "true",
+                            _ => {}
+                        }
+                    }
+                } else if meta.path.is_ident("min_length") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.min_length = lit_str.value().parse::<usize>().ok();
+                        }
+                    }
+                } else if meta.path.is_ident("resize") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.resize = Some(lit_str);
+                        }
+                    }
+                } else if meta.path.is_ident("groups") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.option_groups = Some(parse_option_groups(&lit_str.value()));
+                        }
+                    }
+                } else if meta.path.is_ident("i18n_key") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.i18n_key = Some(lit_str.value());
+                        }
+                    }
+                } else if meta.path.is_ident("tooltip") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.tooltip = Some(lit_str.value());
+                        }
+                    }
+                } else if meta.path.is_ident("min") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.min = lit_str.value().parse::<f64>().ok();
+                        }
+                    }
+                } else if meta.path.is_ident("max") {
+                    if let Ok(value) = meta.value() {
+                        if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                            field_attrs.max = lit_str.value().parse::<f64>().ok();
+                        }
+                    }
                 }
                 Ok(())
             });
         }
     }
 
-    (control_type, default_value, from_type, lorem_count, skip)
+    // RGBA bytes arrive as the color string, parsed back in the From impl
+    if field_attrs.is_rgba_bytes() {
+        field_attrs.from_type = Some(syn::parse_quote!(String));
+    }
+
+    // `Mutable<T>` over a number is edited as the number itself
+    if field_attrs.from_type.is_none() {
+        field_attrs.from_type = numeric_mutable_inner(&field.ty);
+    }
+
+    field_attrs
 }
 
-// Generate lorem ipsum text with specified number of words
-fn generate_lorem_ipsum(word_count: usize) -> String {
-    const LOREM_WORDS: &[&str] = &[
-        "lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing", "elit",
-        "sed", "do", "eiusmod", "tempor", "incididunt", "ut", "labore", "et",
-        "dolore", "magna", "aliqua", "enim", "ad", "minim", "veniam", "quis",
-        "nostrud", "exercitation", "ullamco", "laboris", "nisi", "aliquip", "ex", "ea",
-        "commodo", "consequat", "duis", "aute", "irure", "in", "reprehenderit", "voluptate",
-        "velit", "esse", "cillum", "fugiat", "nulla", "pariatur", "excepteur", "sint",
-        "occaecat", "cupidatat", "non", "proident", "sunt", "culpa", "qui", "officia",
-        "deserunt", "mollit", "anim", "id", "est", "laborum", "pellentesque", "habitant",
-        "morbi", "tristique", "senectus", "netus", "et", "malesuada", "fames", "ac",
-        "turpis", "egestas", "vestibulum", "tortor", "quam", "feugiat", "vitae", "ultricies",
-        "legimus", "typi", "qui", "nusquam", "vici", "sunt", "signa", "consuetudium"
-    ];
-    
-    let mut words = Vec::new();
-    for i in 0..word_count {
-        words.push(LOREM_WORDS[i % LOREM_WORDS.len()]);
+// Helper to parse select option groups, e.g. `size:Small,Medium;weight:Bold,Regular`
+fn parse_option_groups(groups: &str) -> Vec<(String, Vec<String>)> {
+    groups
+        .split(';')
+        .filter_map(|group| {
+            let (group_name, options) = group.split_once(':')?;
+            let options: Vec<String> = options
+                .split(',')
+                .map(|option| option.trim().to_string())
+                .filter(|option| !option.is_empty())
+                .collect();
+            Some((group_name.trim().to_string(), options))
+        })
+        .collect()
+}
+
+// Default of `color_type = "rgba_bytes"` fields, opaque black as a JS literal
+const RGBA_BYTES_DEFAULT: &str = "'rgba(0, 0, 0, 255)'";
+
+// Primitive types that get a number control
+const NUMERIC_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32", "f64",
+];
+
+// Helper to check whether a stringified type is a numeric primitive, optionally in an `Option`,
+// e.g. `Option < f32 >`, but not a collection of them such as `Vec < u8 >` or `[u8 ; 4]`
+fn is_numeric_type(ty_string: &str) -> bool {
+    let Ok(mut ty) = syn::parse_str::<syn::Type>(ty_string) else {
+        return false;
+    };
+    if let Some(inner) = option_inner(&ty) {
+        ty = inner;
     }
-    words.join(" ")
+    let syn::Type::Path(type_path) = &ty else {
+        return false;
+    };
+    type_path.qself.is_none()
+        && type_path.path.segments.iter().all(|segment| segment.arguments.is_none())
+        && type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| NUMERIC_TYPES.contains(&segment.ident.to_string().as_str()))
 }
 
+// Helper to get `T` out of an `Option<T>` type
+fn option_inner(ty: &syn::Type) -> Option<syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner) => Some(inner.clone()),
+        _ => None,
+    }
+}
 
-fn generate_storybook_js(name: &str, _fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>, arg_types: &[(String, String, String, String, String)]) {
-    // Generate argTypes from fields
-    let arg_types_json: Vec<String> = arg_types.iter().map(|(field_name, control, _default_val, required, options_json)| {
-        let options_str = if !options_json.is_empty() {
-            format!(", options: {}", options_json)
-        } else {
-            String::new()
-        };
-        
-        let required_str = if required == "true" {
-            ", table: { category: 'required' }"
-        } else {
-            ""
-        };
-        
-        format!(
-            "    {}: {{\n      control: '{}',\n      description: '{}'{}{}\n    }}",
-            field_name, control, field_name, options_str, required_str
-        )
-    }).collect();
-    
-    let args_str = arg_types_json.join(",\n");
-    
-    // Generate default args
-    let default_args: Vec<String> = arg_types.iter().map(|(field_name, _, default_val, _, _)| {
-        format!("  {}: {}", field_name, default_val)
-    }).collect();
-    
-    let default_args_str = default_args.join(",\n");
-    
-    let js_content = format!(r#"import init, {{ register_all_stories, render_story, get_enum_options, init_enums }} from '../../example/pkg/example.js';
-
-// Initialize WASM
-await init();
-
-console.log('About to call init_enums...');
-init_enums();
-console.log('init_enums called');
-
-register_all_stories();
-
-// Define the story with populated enum options
-export default {{
-  title: 'Components/{}',
-  argTypes: {{
-{}
-  }},
-}};
-
-const Template = (args) => {{
-  const container = document.createElement('div');
-  const dom = render_story('{}', args);
-  container.appendChild(dom);
-  return container;
-}};
-
-export const Default = Template.bind({{}});
-Default.args = {{
-{}
-}};
-"#, name, args_str, name, default_args_str);
-
-    // Write to storybook/stories directory
-    let output_dir = std::env::var("CARGO_MANIFEST_DIR")
-        .map(|d| std::path::PathBuf::from(d).parent().unwrap().join("storybook/stories"))
-        .unwrap_or_else(|_| std::path::PathBuf::from("storybook/stories"));
-    
-    if let Err(_) = std::fs::create_dir_all(&output_dir) {
-        // Directory might already exist, that's fine
+// Helper to get `T` out of a `Mutable<T>` field when `T` is numeric
+fn numeric_mutable_inner(ty: &syn::Type) -> Option<syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Mutable" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner @ syn::Type::Path(inner_path))
+            if args.args.len() == 1
+                && inner_path.path.get_ident().is_some_and(|ident| NUMERIC_TYPES.contains(&ident.to_string().as_str())) =>
+        {
+            Some(inner.clone())
+        }
+        _ => None,
     }
-    
-    let output_file = output_dir.join(format!("{}.stories.js", name));
-    let _ = std::fs::write(output_file, js_content);
 }
 
-/// Attribute macro to document the dominator crate path being used.
-/// 
-/// This is a documentation/metadata attribute that doesn't affect generated code,
-/// but makes it clear which dominator path is being used when it's vendored or re-exported.
-/// 
-/// Usage on a module:
-/// ```ignore
-/// #[storybook::set_dominator_path("crate::vendored::dominator")]
-/// mod stories;
-/// ```
-/// 
-/// This allows specifying a custom path to the dominator crate when it's been vendored
-/// or re-exported from a different location.
-#[proc_macro_attribute]
-pub fn set_dominator_path(_args: TokenStream, input: TokenStream) -> TokenStream {
-    // This is just a marker attribute, it doesn't modify the input
-    // It's used for documentation purposes to indicate which dominator path is in use
-    input
+// Whether a field is a `Mutable<T>`, which live controls update in place
+fn is_mutable_type(ty: &syn::Type) -> bool {
+    let syn::Type::Path(type_path) = ty else {
+        return false;
+    };
+    type_path.path.segments.last().is_some_and(|segment| segment.ident == "Mutable")
 }
 
-#[proc_macro_derive(Story, attributes(story, dominator_crate))]
-pub fn derive_story(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
-    let _dominator_crate = get_dominator_crate_attr(&input);
-    let name = &input.ident;
-    let generics = &input.generics;
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    let name_str = name.to_string();
-    let story_args_name = syn::Ident::new(&format!("{}StoryArgs", name), name.span());
+// Fields that live controls update in place: the story's `Mutable`s that aren't skipped
+fn live_fields(fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>) -> Vec<&syn::Field> {
+    fields
+        .iter()
+        .filter(|field| is_mutable_type(&field.ty) && !get_story_attrs(field).skip)
+        .collect()
+}
 
-    // Extract field information
-    let fields = match &input.data {
-        Data::Struct(data) => match &data.fields {
-            Fields::Named(fields) => &fields.named,
-            _ => panic!("Story can only be derived for structs with named fields"),
-        },
-        _ => panic!("Story can only be derived for structs"),
+// Struct-level `#[story(...)]` settings that apply to the whole story
+#[derive(Default)]
+struct StoryStructAttrs {
+    canvas_width: Option<String>,
+    canvas_padding: Option<String>,
+    canvas_height: Option<String>,
+    // Kept as a literal so unknown presets can be warned about at the attribute
+    default_viewport: Option<syn::LitStr>,
+    stories_dir: Option<String>,
+    component_id: Option<String>,
+    component_story_name: Option<String>,
+    wasm_export_name: Option<syn::Ident>,
+    interact_delay_ms: Option<u32>,
+    // Interval in ms of the `AutoPlay` story cycling through the variants
+    auto_play: Option<u32>,
+    // Kept as a literal so it can be checked against the exports once all attributes are read
+    default_story_variant: Option<syn::LitStr>,
+    // Name and base interfaces of the args interface in the `.stories.d.ts` file
+    export_args_type: Option<String>,
+    export_args_type_extends: Vec<String>,
+    component_docs: Option<String>,
+    note: Option<String>,
+    // Addon panel ID, resolved from the short names of Storybook's own panels
+    panel: Option<String>,
+    aria_label: Option<String>,
+    error_boundary: bool,
+    responsive_preview: bool,
+    render_count: bool,
+    measure_performance: bool,
+    render_once: bool,
+    // Selector of the element the story is mounted into, e.g. `#story-portal`
+    portal_target: Option<String>,
+    screenshot: bool,
+    // `parameters.controls.expanded`, left to Storybook if `None`
+    controls_expanded: Option<bool>,
+    // Columns shown in the Docs args table, all of them if empty
+    arg_table_columns: Vec<String>,
+    // Where `arg_table_columns` was given, to report it clashing with `default_controls_expanded`
+    arg_table_columns_span: Option<proc_macro2::Span>,
+    live_controls: bool,
+    // Args held in `ReactiveStoryArgs`, rendered through `ReactiveStory`
+    reactive: bool,
+    feature_flags: Vec<String>,
+    decorators: Vec<String>,
+    // Component Story Format picked by `storybook_version`
+    csf_version: Option<u8>,
+    // Stories this one is composed of, by name
+    dependencies: Vec<String>,
+    // Keyboard shortcuts as `(key, action)`
+    keyboard_shortcuts: Vec<(String, String)>,
+    generate_play_fn: Option<String>,
+    // Source of the struct definition, shown in the Docs "Show code" panel
+    source_code: Option<String>,
+    // Picked up from `#[aria(role = "...")]` so play assertions can query by role
+    aria_role: Option<String>,
+}
+
+// Viewport presets that ship with Storybook's viewport addon
+const VIEWPORT_PRESETS: &[&str] = &[
+    "mobile1", "mobile2", "tablet", "desktop",
+    "iphone5", "iphone6", "iphone6p", "iphone8p", "iphonex", "iphonexr", "iphonexsmax", "iphonese2",
+    "iphone12", "iphone12promax", "iphone13", "iphone13pro", "iphone13promax",
+    "iphone14", "iphone14pro", "iphone14promax",
+    "ipad", "ipad10p", "ipad11p", "ipad12p",
+    "galaxys5", "galaxys9", "nexus5x", "nexus6p", "pixel", "pixelxl",
+];
+
+// Normalize a viewport name to Storybook's preset key, so "iphone-6" and "iPhone 6 Plus" work,
+// keeping custom viewport names from preview.js as they are
+fn viewport_preset_key(viewport: &str) -> String {
+    let key: String = viewport
+        .chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect();
+    let key = match key.strip_suffix("plus") {
+        Some(prefix) => format!("{}p", prefix),
+        None => key,
     };
+    if VIEWPORT_PRESETS.contains(&key.as_str()) {
+        key
+    } else {
+        viewport.to_string()
+    }
+}
 
-    let story_args_fields = fields.iter().filter_map(|field| {
-        let field_name = &field.ident;
-        let field_ty = &field.ty;
-        let (control_type, _, from_type, _, skip) = get_story_attrs(field);
-        
-        // Skip fields marked with #[story(skip)]
-        if skip {
-            return None;
-        }
-        
-        // Make select control fields optional so they can deserialize from undefined
-        let should_be_optional = control_type.as_ref().map(|c| c == "select").unwrap_or(false);
+// Map the short names of Storybook's built-in panels to their IDs, passing custom addon panel IDs through
+fn addon_panel_id(lit_str: &syn::LitStr) -> syn::Result<String> {
+    let panel = lit_str.value();
+    let id = match panel.trim() {
+        "" => return Err(syn::Error::new(lit_str.span(), "panel must not be empty")),
+        "controls" => "addon-controls",
+        "accessibility" => "storybook/a11y/panel",
+        "interactions" => "storybook/interactions/panel",
+        "actions" => "storybook/actions/panel",
+        custom => custom,
+    };
+    Ok(id.to_string())
+}
 
-        let field_def = if let Some(from_type) = from_type {
-            if should_be_optional {
-                quote! {
-                    #[serde(default)]
-                    pub #field_name: Option<#from_type>
+// Helper to extract story attributes from the struct itself
+// Unlike field attributes these are validated, so mistakes surface as compile errors
+fn get_story_struct_attrs(input: &DeriveInput) -> syn::Result<StoryStructAttrs> {
+    let mut struct_attrs = StoryStructAttrs::default();
+
+    for attr in &input.attrs {
+        if attr.path().is_ident("story") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("width") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.canvas_width = Some(validate_css_dimension(&lit_str)?);
+                } else if meta.path.is_ident("height") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.canvas_height = Some(validate_css_dimension(&lit_str)?);
+                } else if meta.path.is_ident("canvas_padding") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.canvas_padding = Some(validate_canvas_padding(&lit_str)?);
+                } else if meta.path.is_ident("component_story_name") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.component_story_name = Some(validate_story_export_name(&lit_str)?);
+                } else if meta.path.is_ident("wasm_export_name") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    let ident = lit_str.parse::<syn::Ident>().map_err(|_| {
+                        syn::Error::new(lit_str.span(), format!("invalid wasm_export_name '{}', expected a function name", lit_str.value()))
+                    })?;
+                    struct_attrs.wasm_export_name = Some(ident);
+                } else if meta.path.is_ident("stories_dir") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.stories_dir = Some(lit_str.value());
+                } else if meta.path.is_ident("component_id") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.component_id = Some(validate_component_id(&lit_str)?);
+                } else if meta.path.is_ident("interact_delay_ms") {
+                    let lit_int = meta.value()?.parse::<syn::LitInt>()?;
+                    let delay = lit_int.base10_parse::<u32>()?;
+                    if delay == 0 {
+                        return Err(syn::Error::new(lit_int.span(), "interact_delay_ms must be a positive integer"));
+                    }
+                    struct_attrs.interact_delay_ms = Some(delay);
+                } else if meta.path.is_ident("export_args_type") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    if !is_js_identifier(&lit_str.value()) {
+                        return Err(syn::Error::new(lit_str.span(), "export_args_type must be a TypeScript identifier, e.g. \"ButtonControlArgs\""));
+                    }
+                    struct_attrs.export_args_type = Some(lit_str.value());
+                } else if meta.path.is_ident("export_args_type_extends") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    let bases: Vec<String> = lit_str.value().split(',').map(|base| base.trim().to_string()).collect();
+                    // Bases may be namespaced, e.g. `Shared.BaseArgs`
+                    if !bases.iter().all(|base| base.split('.').all(is_js_identifier)) {
+                        return Err(syn::Error::new(lit_str.span(), "export_args_type_extends must be TypeScript interface names, e.g. \"BaseButtonArgs\""));
+                    }
+                    struct_attrs.export_args_type_extends = bases;
+                } else if meta.path.is_ident("auto_play") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    let interval = lit_str
+                        .value()
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|interval| *interval > 100)
+                        .ok_or_else(|| syn::Error::new(lit_str.span(), "auto_play must be an interval in ms above 100, e.g. \"3000\""))?;
+                    struct_attrs.auto_play = Some(interval);
+                } else if meta.path.is_ident("default_story_variant") {
+                    struct_attrs.default_story_variant = Some(meta.value()?.parse::<syn::LitStr>()?);
+                } else if meta.path.is_ident("component_docs") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.component_docs = Some(lit_str.value());
+                } else if meta.path.is_ident("note") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.note = Some(lit_str.value());
+                } else if meta.path.is_ident("default_viewport") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    if lit_str.value().trim().is_empty() {
+                        return Err(syn::Error::new(lit_str.span(), "default_viewport must not be empty"));
+                    }
+                    struct_attrs.default_viewport = Some(lit_str);
+                } else if meta.path.is_ident("panel") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.panel = Some(addon_panel_id(&lit_str)?);
+                } else if meta.path.is_ident("aria_label") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.aria_label = Some(lit_str.value());
+                } else if meta.path.is_ident("generate_play_fn") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    if lit_str.value() != "interactions" {
+                        return Err(syn::Error::new(lit_str.span(), "generate_play_fn must be \"interactions\""));
+                    }
+                    struct_attrs.generate_play_fn = Some(lit_str.value());
+                } else if meta.path.is_ident("feature_flag") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    if lit_str.value().trim().is_empty() {
+                        return Err(syn::Error::new(lit_str.span(), "feature_flag must not be empty"));
+                    }
+                    struct_attrs.feature_flags.push(lit_str.value().trim().to_string());
+                } else if meta.path.is_ident("storybook_decorator") || meta.path.is_ident("storybook_decorators") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.decorators.extend(parse_decorators(&lit_str)?);
+                } else if meta.path.is_ident("storybook_version") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    let csf_version = csf_version_for(&lit_str.value())
+                        .ok_or_else(|| syn::Error::new(lit_str.span(), "storybook_version must be \"6\", \"7\" or \"8\""))?;
+                    struct_attrs.csf_version = Some(csf_version);
+                } else if meta.path.is_ident("keyboard") {
+                    meta.parse_nested_meta(|shortcut| {
+                        let key = shortcut
+                            .path
+                            .get_ident()
+                            .ok_or_else(|| shortcut.error("expected a key name such as Escape"))?
+                            .to_string();
+                        let lit_str = shortcut.value()?.parse::<syn::LitStr>()?;
+                        if lit_str.value().trim().is_empty() {
+                            return Err(syn::Error::new(lit_str.span(), "keyboard shortcut description must not be empty"));
+                        }
+                        struct_attrs.keyboard_shortcuts.push((key, lit_str.value()));
+                        Ok(())
+                    })?;
+                } else if meta.path.is_ident("depends_on") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.dependencies.extend(parse_story_names(&lit_str)?);
+                } else if meta.path.is_ident("responsive_preview") {
+                    struct_attrs.responsive_preview = true;
+                } else if meta.path.is_ident("render_count") {
+                    struct_attrs.render_count = true;
+                } else if meta.path.is_ident("measure_performance") {
+                    struct_attrs.measure_performance = true;
+                } else if meta.path.is_ident("once") {
+                    struct_attrs.render_once = true;
+                } else if meta.path.is_ident("portal_target") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    let target = lit_str.value();
+                    // Only IDs, as a missing target is created with that ID
+                    let is_id_selector = target
+                        .strip_prefix('#')
+                        .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+                    if !is_id_selector {
+                        return Err(syn::Error::new(lit_str.span(), "portal_target must be an ID selector, e.g. \"#story-portal\""));
+                    }
+                    struct_attrs.portal_target = Some(target);
+                } else if meta.path.is_ident("screenshot") {
+                    struct_attrs.screenshot = true;
+                } else if meta.path.is_ident("default_controls_expanded") {
+                    // Either `= true` or `= "true"`
+                    let expanded = match meta.value()?.parse::<syn::Lit>()? {
+                        syn::Lit::Bool(lit_bool) => lit_bool.value,
+                        syn::Lit::Str(lit_str) => match lit_str.value().as_str() {
+                            "true" => true,
+                            "false" => false,
+                            _ => return Err(syn::Error::new(lit_str.span(), "default_controls_expanded must be \"true\" or \"false\"")),
+                        },
+                        lit => return Err(syn::Error::new(lit.span(), "default_controls_expanded must be true or false")),
+                    };
+                    struct_attrs.controls_expanded = Some(expanded);
+                } else if meta.path.is_ident("arg_table_columns") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    struct_attrs.arg_table_columns = parse_arg_table_columns(&lit_str)?;
+                    struct_attrs.arg_table_columns_span = Some(lit_str.span());
+                } else if meta.path.is_ident("live_controls") {
+                    struct_attrs.live_controls = true;
+                } else if meta.path.is_ident("reactive") {
+                    struct_attrs.reactive = true;
+                } else if meta.path.is_ident("error_boundary") {
+                    struct_attrs.error_boundary = true;
+                } else if meta.path.is_ident("live_preview") {
+                    struct_attrs.source_code = Some(struct_source(input));
+                } else {
+                    return Err(meta.error("unknown story attribute"));
                 }
-            } else {
-                quote! {
-                    #[serde(default)]
-                    pub #field_name: #from_type
+                Ok(())
+            })?;
+        } else if attr.path().is_ident("aria") {
+            // `AriaMetadata` validates these, so only the role is read here
+            let _ = attr.parse_nested_meta(|meta| {
+                if let Ok(value) = meta.value() {
+                    if let Ok(lit_str) = value.parse::<syn::LitStr>() {
+                        if meta.path.is_ident("role") {
+                            struct_attrs.aria_role = Some(lit_str.value());
+                        }
+                    }
                 }
-            }
-        } else {
-            if should_be_optional {
+                Ok(())
+            });
+        }
+    }
+
+    // Fall back to the struct's doc comments for the component description
+    if struct_attrs.component_docs.is_none() {
+        struct_attrs.component_docs = get_doc_comment(&input.attrs);
+    }
+
+    if let (Some(span), Some(true)) = (struct_attrs.arg_table_columns_span, struct_attrs.controls_expanded) {
+        if struct_attrs.hides_arg_table_column("description") {
+            return Err(syn::Error::new(
+                span,
+                "hiding the description and default columns collapses the controls, so it can't be combined with `default_controls_expanded = true`",
+            ));
+        }
+    }
+
+    // The story file only exports the main variant, and `AutoPlay` with `auto_play`
+    if let Some(lit_str) = &struct_attrs.default_story_variant {
+        let export_name = struct_attrs.component_story_name.as_deref().unwrap_or("Default");
+        let variant = lit_str.value();
+        if variant != export_name && (variant != "AutoPlay" || struct_attrs.auto_play.is_none()) {
+            let exports = match struct_attrs.auto_play {
+                Some(_) => format!("'{}' or 'AutoPlay'", export_name),
+                None => format!("'{}', or 'AutoPlay' with `auto_play`", export_name),
+            };
+            return Err(syn::Error::new(
+                lit_str.span(),
+                format!("unknown default_story_variant '{}', the story exports {}", variant, exports),
+            ));
+        }
+    }
+
+    Ok(struct_attrs)
+}
+
+impl StoryStructAttrs {
+    // Whether `arg_table_columns` leaves out the column
+    fn hides_arg_table_column(&self, column: &str) -> bool {
+        !self.arg_table_columns.is_empty() && !self.arg_table_columns.iter().any(|shown| shown == column)
+    }
+
+    // `parameters.controls.expanded`, collapsed when the description and default columns are hidden
+    fn controls_expanded_param(&self) -> Option<bool> {
+        if self.hides_arg_table_column("description") {
+            return Some(false);
+        }
+        self.controls_expanded
+    }
+}
+
+// Helper to get the struct's definition as written, with its attributes and doc comments
+// Falls back to the struct's tokens when the source file can't be read
+fn struct_source(input: &DeriveInput) -> String {
+    let span = input.ident.span().unwrap();
+    let line = span.line();
+    let source = std::fs::read_to_string(span.local_file().unwrap_or_default()).ok();
+
+    source
+        .and_then(|source| extract_struct_source(&source, line))
+        .unwrap_or_else(|| quote!(#input).to_string())
+}
+
+// Helper to cut a struct definition out of a source file, given the line its name is on
+fn extract_struct_source(source: &str, ident_line: usize) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let ident_index = ident_line.checked_sub(1)?;
+
+    // Walk back over the attributes and doc comments above the struct
+    let mut start = ident_index;
+    while start > 0 {
+        let previous = lines[start - 1].trim_start();
+        if previous.starts_with("#[") || previous.starts_with("///") {
+            start -= 1;
+        } else {
+            break;
+        }
+    }
+
+    // Walk forward to the brace closing the struct body, skipping braces inside strings
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, line) in lines.iter().enumerate().skip(ident_index) {
+        for c in line.chars() {
+            if in_string {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                '"' => in_string = true,
+                '{' => depth += 1,
+                '}' => {
+                    depth = depth.checked_sub(1)?;
+                    if depth == 0 {
+                        return Some(lines[start..=index].join("\n"));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    None
+}
+
+// Helper to collect `///` doc comments into a single string
+fn get_doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit_str), .. }),
+                ..
+            }) => Some(lit_str.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).to_string())
+        .collect();
+
+    let docs = lines.join("\n").trim().to_string();
+    if docs.is_empty() {
+        None
+    } else {
+        Some(docs)
+    }
+}
+
+// Check that a CSS dimension is a number followed by a supported unit, e.g. `300px` or `50%`
+fn validate_css_dimension(lit_str: &syn::LitStr) -> syn::Result<String> {
+    const UNITS: &[&str] = &["px", "%", "em", "rem", "vh", "vw"];
+
+    let value = lit_str.value();
+    let is_valid = UNITS.iter().any(|unit| {
+        value
+            .strip_suffix(unit)
+            .and_then(|number| number.parse::<f64>().ok())
+            .map(|number| number.is_finite())
+            .unwrap_or(false)
+    });
+
+    if is_valid {
+        Ok(value)
+    } else {
+        Err(syn::Error::new(
+            lit_str.span(),
+            format!("invalid CSS dimension '{}', expected a number followed by px, %, em, rem, vh or vw", value),
+        ))
+    }
+}
+
+// Resolve a canvas padding to CSS: a named size (`sm`, `md`, `lg`) or 1-4 dimensions, e.g. `10px 20px`
+fn validate_canvas_padding(lit_str: &syn::LitStr) -> syn::Result<String> {
+    const NAMED_SIZES: &[(&str, &str)] = &[("sm", "8px"), ("md", "16px"), ("lg", "24px")];
+
+    let value = lit_str.value();
+    if let Some((_, px)) = NAMED_SIZES.iter().find(|(name, _)| *name == value) {
+        return Ok(px.to_string());
+    }
+
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    if parts.is_empty() || parts.len() > 4 {
+        return Err(syn::Error::new(
+            lit_str.span(),
+            format!("invalid canvas padding '{}', expected sm, md, lg or 1 to 4 CSS dimensions", value),
+        ));
+    }
+
+    let dimensions = parts
+        .iter()
+        .map(|part| match *part {
+            "0" => Ok("0".to_string()),
+            part => validate_css_dimension(&syn::LitStr::new(part, lit_str.span())),
+        })
+        .collect::<syn::Result<Vec<String>>>()?;
+
+    Ok(dimensions.join(" "))
+}
+
+// Split `bool_labels` into its true and false labels, checking there are exactly two
+fn split_bool_labels(lit_str: &syn::LitStr) -> syn::Result<(String, String)> {
+    let value = lit_str.value();
+    match value.split(',').collect::<Vec<_>>().as_slice() {
+        [true_label, false_label] => Ok((true_label.trim().to_string(), false_label.trim().to_string())),
+        _ => Err(syn::Error::new(
+            lit_str.span(),
+            format!("invalid bool_labels '{}', expected two comma-separated labels, e.g. \"Yes,No\"", value),
+        )),
+    }
+}
+
+// The story's Storybook title; flagged stories move to their own folder when experimental stories are hidden
+fn story_title(name: &str, struct_attrs: &StoryStructAttrs) -> String {
+    let hide_flagged = std::env::var("STORYBOOK_SHOW_EXPERIMENTAL").is_ok_and(|show| show == "false");
+    if hide_flagged && !struct_attrs.feature_flags.is_empty() {
+        format!("Feature Flagged/{}", name)
+    } else {
+        format!("Components/{}", name)
+    }
+}
+
+// Check that a textarea resize mode is a CSS `resize` value
+fn validate_resize(lit_str: &syn::LitStr) -> syn::Result<String> {
+    const RESIZE_MODES: &[&str] = &["none", "both", "horizontal", "vertical"];
+
+    let value = lit_str.value();
+    if RESIZE_MODES.contains(&value.as_str()) {
+        Ok(value)
+    } else {
+        Err(syn::Error::new(
+            lit_str.span(),
+            format!("invalid resize '{}', expected none, both, horizontal or vertical", value),
+        ))
+    }
+}
+
+// Check that a Storybook component ID only uses lowercase letters, digits and hyphens
+fn validate_component_id(lit_str: &syn::LitStr) -> syn::Result<String> {
+    let value = lit_str.value();
+    let is_valid = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+
+    if is_valid {
+        Ok(value)
+    } else {
+        Err(syn::Error::new(
+            lit_str.span(),
+            format!("invalid component ID '{}', expected lowercase letters, digits and hyphens", value),
+        ))
+    }
+}
+
+// Check that a story export name is a JS identifier, e.g. `Enabled`
+fn validate_story_export_name(lit_str: &syn::LitStr) -> syn::Result<String> {
+    let value = lit_str.value();
+    if is_js_identifier(&value) {
+        Ok(value)
+    } else {
+        Err(syn::Error::new(
+            lit_str.span(),
+            format!("invalid story name '{}', expected a JS identifier such as 'Enabled'", value),
+        ))
+    }
+}
+
+// Parse a comma-separated list of JS decorator names, e.g. `withRouter,withTheme`
+fn parse_decorators(lit_str: &syn::LitStr) -> syn::Result<Vec<String>> {
+    lit_str
+        .value()
+        .split(',')
+        .map(str::trim)
+        .filter(|decorator| !decorator.is_empty())
+        .map(|decorator| {
+            if is_js_identifier(decorator) {
+                Ok(decorator.to_string())
+            } else {
+                Err(syn::Error::new(
+                    lit_str.span(),
+                    format!("invalid decorator '{}', expected a JS identifier such as 'withRouter'", decorator),
+                ))
+            }
+        })
+        .collect()
+}
+
+// Columns of Storybook's Docs args table, kept in sync with `storybook::ARG_TABLE_COLUMNS`
+const ARG_TABLE_COLUMNS: &[&str] = &["name", "type", "default", "description", "required", "control"];
+
+// Parse a comma-separated list of args table columns such as `name,description,default`
+fn parse_arg_table_columns(lit_str: &syn::LitStr) -> syn::Result<Vec<String>> {
+    let mut columns: Vec<String> = Vec::new();
+    for column in lit_str.value().split(',').map(str::trim).filter(|column| !column.is_empty()) {
+        if !ARG_TABLE_COLUMNS.contains(&column) {
+            return Err(syn::Error::new(
+                lit_str.span(),
+                format!("unknown args table column '{}', expected one of: {}", column, ARG_TABLE_COLUMNS.join(", ")),
+            ));
+        }
+        if !columns.iter().any(|listed| listed == column) {
+            columns.push(column.to_string());
+        }
+    }
+    if columns.is_empty() {
+        return Err(syn::Error::new(lit_str.span(), "arg_table_columns must list at least one column"));
+    }
+    // Storybook has no per-column switch, so only columns with some other way to hide them can be left out
+    let shown = |column: &str| columns.iter().any(|listed| listed == column);
+    if !shown("name") {
+        return Err(syn::Error::new(lit_str.span(), "the name column can't be hidden, add it to arg_table_columns"));
+    }
+    if shown("description") != shown("default") {
+        return Err(syn::Error::new(
+            lit_str.span(),
+            "the description and default columns can only be hidden together, by collapsing the controls",
+        ));
+    }
+    Ok(columns)
+}
+
+// Parse an aspect ratio written as `16/9` or `1.5`
+fn parse_aspect_ratio(value: &str) -> Option<f64> {
+    let ratio = match value.split_once('/') {
+        Some((width, height)) => width.trim().parse::<f64>().ok()? / height.trim().parse::<f64>().ok()?,
+        None => value.trim().parse::<f64>().ok()?,
+    };
+    (ratio.is_finite() && ratio > 0.0).then_some(ratio)
+}
+
+// Storybook 6 uses bound templates (CSF2), 7 and later story objects (CSF3)
+fn csf_version_for(storybook_version: &str) -> Option<u8> {
+    match storybook_version.trim() {
+        "6" => Some(2),
+        "7" | "8" => Some(3),
+        _ => None,
+    }
+}
+
+// CSF version picked by the `STORYBOOK_VERSION` environment variable, if it's set
+fn env_csf_version() -> Result<Option<u8>, String> {
+    match std::env::var("STORYBOOK_VERSION") {
+        Ok(version) => csf_version_for(&version)
+            .map(Some)
+            .ok_or_else(|| format!("STORYBOOK_VERSION must be \"6\", \"7\" or \"8\", found \"{}\"", version)),
+        Err(_) => Ok(None),
+    }
+}
+
+// Parse a comma-separated list of story names, which are the names of the story structs
+fn parse_story_names(lit_str: &syn::LitStr) -> syn::Result<Vec<String>> {
+    lit_str
+        .value()
+        .split(',')
+        .map(str::trim)
+        .filter(|story| !story.is_empty())
+        .map(|story| match syn::parse_str::<syn::Ident>(story) {
+            Ok(_) => Ok(story.to_string()),
+            Err(_) => Err(syn::Error::new(
+                lit_str.span(),
+                format!("invalid story name '{}', expected a struct name such as 'Button'", story),
+            )),
+        })
+        .collect()
+}
+
+// Parse a comma-separated list of composed stories such as `Button::Disabled, Card`,
+// as `(story, variant)` with no variant meaning the story's primary one
+fn parse_composed_stories(lit_str: &syn::LitStr) -> syn::Result<Vec<(String, Option<String>)>> {
+    let stories = lit_str
+        .value()
+        .split(',')
+        .map(str::trim)
+        .filter(|story| !story.is_empty())
+        .map(|story| {
+            let path = syn::parse_str::<syn::Path>(story).ok().filter(|path| {
+                path.leading_colon.is_none()
+                    && path.segments.len() <= 2
+                    && path.segments.iter().all(|segment| segment.arguments.is_none())
+            });
+            match path {
+                Some(path) => {
+                    let mut segments = path.segments.iter().map(|segment| segment.ident.to_string());
+                    Ok((segments.next().unwrap(), segments.next()))
+                }
+                None => Err(syn::Error::new(
+                    lit_str.span(),
+                    format!("invalid story '{}', expected a story such as 'Button' or 'Button::Disabled'", story),
+                )),
+            }
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    if stories.is_empty() {
+        return Err(syn::Error::new(lit_str.span(), "expected at least one story to compose"));
+    }
+    Ok(stories)
+}
+
+// Rebuild a story template at runtime, so `storybook-codegen` renders the same file as the one written here
+fn story_template_tokens(template: &StoryTemplate) -> proc_macro2::TokenStream {
+    fn strings(values: &[String]) -> proc_macro2::TokenStream {
+        quote! { vec![#(#values.to_string()),*] }
+    }
+    fn option_string(value: &Option<String>) -> proc_macro2::TokenStream {
+        match value {
+            Some(value) => quote! { Some(#value.to_string()) },
+            None => quote! { None },
+        }
+    }
+
+    let StoryTemplate {
+        name, wasm_module, title, component_id, decorators, screenshot, arg_types, parameters, viewport, portal_target,
+        live_fields, measure_performance, export_name, csf3, test_imports, play_steps, auto_play, default_variant,
+    } = template;
+    let component_id = option_string(component_id);
+    let decorators = strings(decorators);
+    let arg_types = arg_types.iter().map(|arg_type| {
+        let TemplateArgType { name, control, description, options, table, extra, default_value, percentage, dependent_options } = arg_type;
+        let options = option_string(options);
+        let table = strings(table);
+        let extra = strings(extra);
+        let default_value = option_string(default_value);
+        quote! {
+            storybook::template::TemplateArgType {
+                name: #name.to_string(),
+                control: #control.to_string(),
+                description: #description.to_string(),
+                options: #options,
+                table: #table,
+                extra: #extra,
+                default_value: #default_value,
+                percentage: #percentage,
+                dependent_options: #dependent_options,
+            }
+        }
+    });
+    let parameters = strings(parameters);
+    let viewport = option_string(viewport);
+    let portal_target = option_string(portal_target);
+    let live_fields = match live_fields {
+        Some(live_fields) => {
+            let live_fields = strings(live_fields);
+            quote! { Some(#live_fields) }
+        }
+        None => quote! { None },
+    };
+    let test_imports = strings(test_imports);
+    let play_steps = strings(play_steps);
+    let auto_play = match auto_play {
+        Some(interval) => quote! { Some(#interval) },
+        None => quote! { None },
+    };
+    let default_variant = option_string(default_variant);
+
+    quote! {
+        storybook::template::StoryTemplate {
+            name: #name.to_string(),
+            wasm_module: #wasm_module.to_string(),
+            title: #title.to_string(),
+            component_id: #component_id,
+            decorators: #decorators,
+            screenshot: #screenshot,
+            arg_types: vec![#(#arg_types),*],
+            parameters: #parameters,
+            viewport: #viewport,
+            portal_target: #portal_target,
+            live_fields: #live_fields,
+            measure_performance: #measure_performance,
+            export_name: #export_name.to_string(),
+            csf3: #csf3,
+            test_imports: #test_imports,
+            play_steps: #play_steps,
+            auto_play: #auto_play,
+            default_variant: #default_variant,
+        }
+    }
+}
+
+// Quote an optional string as an `Option<&'static str>` expression
+fn quote_option_str(value: &Option<String>) -> proc_macro2::TokenStream {
+    match value {
+        Some(v) => quote! { Some(#v) },
+        None => quote! { None },
+    }
+}
+
+// Generate lorem ipsum text with specified number of words
+fn generate_lorem_ipsum(word_count: usize) -> String {
+    const LOREM_WORDS: &[&str] = &[
+        "lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing", "elit",
+        "sed", "do", "eiusmod", "tempor", "incididunt", "ut", "labore", "et",
+        "dolore", "magna", "aliqua", "enim", "ad", "minim", "veniam", "quis",
+        "nostrud", "exercitation", "ullamco", "laboris", "nisi", "aliquip", "ex", "ea",
+        "commodo", "consequat", "duis", "aute", "irure", "in", "reprehenderit", "voluptate",
+        "velit", "esse", "cillum", "fugiat", "nulla", "pariatur", "excepteur", "sint",
+        "occaecat", "cupidatat", "non", "proident", "sunt", "culpa", "qui", "officia",
+        "deserunt", "mollit", "anim", "id", "est", "laborum", "pellentesque", "habitant",
+        "morbi", "tristique", "senectus", "netus", "et", "malesuada", "fames", "ac",
+        "turpis", "egestas", "vestibulum", "tortor", "quam", "feugiat", "vitae", "ultricies",
+        "legimus", "typi", "qui", "nusquam", "vici", "sunt", "signa", "consuetudium"
+    ];
+    
+    let mut words = Vec::new();
+    for i in 0..word_count {
+        words.push(LOREM_WORDS[i % LOREM_WORDS.len()]);
+    }
+    words.join(" ")
+}
+
+
+// Arg type information for a single field, as written to the `.stories.js` file
+// `control`, `default_value` and `options` hold JS expressions rather than plain strings
+struct JsArgType {
+    name: String,
+    control: String,
+    default_value: String,
+    required: bool,
+    options: Option<String>,
+    // Additional `key: value` properties appended to the argType
+    extra: Vec<String>,
+    // Additional `key: value` properties of the argType's docs table entry
+    table: Vec<String>,
+    // Args whose values this arg's select options depend on
+    depends_on: Vec<String>,
+    // Whether the options come from `get_dependent_options`
+    dependent_options: bool,
+    // Whether the control shows the value multiplied by 100, divided again before rendering
+    percentage: bool,
+    // TypeScript type of the arg's value, for the `.stories.d.ts` file
+    ts_type: String,
+}
+
+fn generate_storybook_js(name: &str, fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>, arg_types: &[JsArgType], struct_attrs: &StoryStructAttrs, config: Option<&LoadedConfig>) -> Result<(std::path::PathBuf, StoryTemplate), String> {
+    let stories_config = config.map(|config| &config.config.stories);
+
+    // Hidden args table columns are hidden on every arg, like `StoryRegistration::hide_arg_table_columns`
+    let template_arg_types: Vec<TemplateArgType> = arg_types.iter().map(|arg_type| {
+        let mut table = Vec::new();
+        if arg_type.required && !struct_attrs.hides_arg_table_column("required") {
+            table.push("category: 'required'".to_string());
+        }
+        if struct_attrs.hides_arg_table_column("type") {
+            table.extend(arg_type.table.iter().filter(|entry| !entry.starts_with("type:")).cloned());
+            table.push("type: { summary: null }".to_string());
+        } else {
+            table.extend(arg_type.table.iter().cloned());
+        }
+        let control = if struct_attrs.hides_arg_table_column("control") { "false".to_string() } else { arg_type.control.clone() };
+
+        TemplateArgType {
+            name: arg_type.name.clone(),
+            control,
+            description: js_string_literal(&arg_type.name),
+            options: arg_type.options.clone(),
+            table,
+            extra: arg_type.extra.clone(),
+            default_value: Some(arg_type.default_value.clone()),
+            percentage: arg_type.percentage,
+            dependent_options: arg_type.dependent_options,
+        }
+    }).collect();
+
+    // Generate story parameters from struct-level attributes
+    let mut parameters: Vec<String> = Vec::new();
+    if struct_attrs.canvas_width.is_some() || struct_attrs.canvas_height.is_some() {
+        let mut canvas_layout = Vec::new();
+        if let Some(width) = &struct_attrs.canvas_width {
+            canvas_layout.push(format!("width: '{}'", width));
+        }
+        if let Some(height) = &struct_attrs.canvas_height {
+            canvas_layout.push(format!("height: '{}'", height));
+        }
+        parameters.push("layout: 'padded'".to_string());
+        parameters.push(format!("canvasLayout: {{ {} }}", canvas_layout.join(", ")));
+    } else if struct_attrs.canvas_padding.is_some() {
+        parameters.push("layout: 'padded'".to_string());
+    } else if let Some(layout) = stories_config.and_then(|stories| stories.default_layout.as_ref()) {
+        parameters.push(format!("layout: '{}'", layout));
+    }
+
+    if let Some(padding) = &struct_attrs.canvas_padding {
+        parameters.push(format!("canvasPadding: '{}'", padding));
+    }
+
+    if let Some(note) = &struct_attrs.note {
+        parameters.push(format!("notes: {}", js_text_literal(note)));
+    }
+
+    if let Some(panel) = &struct_attrs.panel {
+        parameters.push(format!("options: {{ showPanel: true, selectedPanel: {} }}", js_string_literal(panel)));
+    }
+
+    let depends_on: Vec<String> = arg_types
+        .iter()
+        .filter(|arg_type| !arg_type.depends_on.is_empty())
+        .map(|arg_type| {
+            let fields: Vec<String> = arg_type.depends_on.iter().map(|field| js_string_literal(field)).collect();
+            format!("{}: [{}]", arg_type.name, fields.join(", "))
+        })
+        .collect();
+    let mut controls: Vec<String> = Vec::new();
+    if !depends_on.is_empty() {
+        controls.push(format!("dependsOn: {{ {} }}", depends_on.join(", ")));
+    }
+    if let Some(expanded) = struct_attrs.controls_expanded_param() {
+        controls.push(format!("expanded: {}", expanded));
+    }
+    if !controls.is_empty() {
+        parameters.push(format!("controls: {{ {} }}", controls.join(", ")));
+    }
+
+    if !struct_attrs.keyboard_shortcuts.is_empty() {
+        let shortcuts: Vec<String> = struct_attrs
+            .keyboard_shortcuts
+            .iter()
+            .map(|(key, action)| format!("{}: {}", key, js_string_literal(action)))
+            .collect();
+        parameters.push(format!("keyboard: {{ {} }}", shortcuts.join(", ")));
+    }
+
+    if !struct_attrs.feature_flags.is_empty() {
+        let flags: Vec<String> = struct_attrs.feature_flags.iter().map(|flag| js_string_literal(flag)).collect();
+        parameters.push(format!("featureFlags: [{}]", flags.join(", ")));
+    }
+
+    let mut docs: Vec<String> = Vec::new();
+    if let Some(component_docs) = &struct_attrs.component_docs {
+        docs.push(format!("description: {{ component: {} }}", js_text_literal(component_docs)));
+    }
+    if let Some(source_code) = &struct_attrs.source_code {
+        docs.push(format!("source: {{ code: {}, language: 'rust' }}", js_template_literal(source_code)));
+    }
+    if !docs.is_empty() {
+        parameters.push(format!("docs: {{ {} }}", docs.join(", ")));
+    }
+
+    // Generate the play function, run by Storybook once the story has rendered
+    let mut play_steps: Vec<String> = Vec::new();
+    if let Some(delay_ms) = struct_attrs.interact_delay_ms {
+        play_steps.push("const delay = (ms) => new Promise(r => setTimeout(r, ms));".to_string());
+        play_steps.push(format!("await delay({});", delay_ms));
+    }
+
+    // Testing Library helpers used by the play function, imported from `storybook/test`
+    let mut test_imports: Vec<String> = Vec::new();
+    let mut use_test_import = |play_steps: &mut Vec<String>, imports: &[&'static str]| {
+        if test_imports.is_empty() {
+            play_steps.push("const canvas = within(canvasElement);".to_string());
+            test_imports.push("within".to_string());
+        }
+        for import in imports {
+            if !test_imports.iter().any(|imported| imported == import) {
+                test_imports.push(import.to_string());
+            }
+        }
+    };
+
+    // Assert the root element exposes the documented accessible name
+    if let Some(aria_label) = &struct_attrs.aria_label {
+        use_test_import(&mut play_steps, &["expect"]);
+        let query = match &struct_attrs.aria_role {
+            Some(role) => format!("canvas.getByRole({}, {{ name: {} }})", js_string_literal(role), js_string_literal(aria_label)),
+            None => format!("canvas.getByLabelText({})", js_string_literal(aria_label)),
+        };
+        play_steps.push(format!("await expect({}).toBeInTheDocument();", query));
+    }
+
+    // Exercise the interactions implied by well-known field names
+    if struct_attrs.generate_play_fn.is_some() {
+        let field_names: Vec<String> = fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref().map(|ident| ident.to_string()))
+            .collect();
+        let has_field = |field_name: &str| field_names.iter().any(|name| name == field_name);
+        let role = js_string_literal(struct_attrs.aria_role.as_deref().unwrap_or("button"));
+
+        if has_field("disabled") {
+            use_test_import(&mut play_steps, &["expect"]);
+            // Switch controls map the value to 'on'/'off'
+            play_steps.push("const isDisabled = [true, 'on'].includes(args.disabled);".to_string());
+            play_steps.push("if (isDisabled) {".to_string());
+            play_steps.push(format!("  await expect(canvas.getByRole({})).toBeDisabled();", role));
+            play_steps.push("}".to_string());
+        }
+        if has_field("on_click") {
+            use_test_import(&mut play_steps, &["userEvent"]);
+            let click = format!("await userEvent.click(canvas.getByRole({}));", role);
+            if has_field("disabled") {
+                // Disabled elements can't be clicked
+                play_steps.push(format!("if (!isDisabled) {{\n    {}\n  }}", click));
+            } else {
+                play_steps.push(click);
+            }
+        }
+        if has_field("value") {
+            use_test_import(&mut play_steps, &["userEvent"]);
+            play_steps.push("await userEvent.clear(canvas.getByRole('textbox'));".to_string());
+            play_steps.push("await userEvent.type(canvas.getByRole('textbox'), 'Hello, Storybook');".to_string());
+        }
+        if has_field("checked") {
+            use_test_import(&mut play_steps, &["userEvent"]);
+            play_steps.push("await userEvent.click(canvas.getByRole('checkbox'));".to_string());
+        }
+    }
+
+    // The story's own `storybook_version` wins over `STORYBOOK_VERSION`, which wins over storybook.toml
+    let csf_version = struct_attrs
+        .csf_version
+        .or(env_csf_version()?)
+        .or_else(|| stories_config.and_then(|stories| stories.csf_version));
+
+    // Live stories update the `Mutable` fields in place, reactive stories any field
+    let live_fields = (struct_attrs.live_controls || struct_attrs.reactive).then(|| {
+        let updated_fields = if struct_attrs.reactive {
+            fields.iter().filter(|field| !get_story_attrs(field).skip).collect()
+        } else {
+            live_fields(fields)
+        };
+        updated_fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref().map(|ident| ident.to_string()))
+            .collect()
+    });
+
+    let template = StoryTemplate {
+        name: name.to_string(),
+        wasm_module: DEFAULT_WASM_MODULE.to_string(),
+        title: story_title(name, struct_attrs),
+        component_id: struct_attrs.component_id.clone(),
+        decorators: struct_attrs.decorators.clone(),
+        screenshot: struct_attrs.screenshot,
+        arg_types: template_arg_types,
+        parameters,
+        viewport: struct_attrs.default_viewport.as_ref().map(|viewport| viewport_preset_key(&viewport.value())),
+        portal_target: struct_attrs.portal_target.as_ref().map(|portal_target| portal_target.trim_start_matches('#').to_string()),
+        live_fields,
+        measure_performance: struct_attrs.measure_performance,
+        export_name: struct_attrs.component_story_name.clone().unwrap_or_else(|| "Default".to_string()),
+        csf3: csf_version == Some(3),
+        test_imports,
+        play_steps,
+        auto_play: struct_attrs.auto_play,
+        default_variant: struct_attrs.default_story_variant.as_ref().map(syn::LitStr::value),
+    };
+
+    let output_dir = stories_output_dir(struct_attrs.stories_dir.as_deref(), config);
+    Ok((output_dir.join(format!("{}.stories.js", name)), template))
+}
+
+// Write a generated `.stories.js` file, creating its directory if needed
+fn write_story_file(output_file: &std::path::Path, js_content: &str) -> std::io::Result<()> {
+    if let Some(output_dir) = output_file.parent() {
+        std::fs::create_dir_all(output_dir)?;
+    }
+    std::fs::write(output_file, js_content)
+}
+
+// Generate the `.stories.d.ts` declaring the story's args, for stories with `export_args_type(_extends)`
+fn generate_story_types(name: &str, arg_types: &[JsArgType], struct_attrs: &StoryStructAttrs) -> Option<String> {
+    if struct_attrs.export_args_type.is_none() && struct_attrs.export_args_type_extends.is_empty() {
+        return None;
+    }
+
+    let interface_name = struct_attrs.export_args_type.clone().unwrap_or_else(|| format!("{}Args", name));
+    let extends_str = if struct_attrs.export_args_type_extends.is_empty() {
+        String::new()
+    } else {
+        format!(" extends {}", struct_attrs.export_args_type_extends.join(", "))
+    };
+    // Optional fields may be left out or sent as null
+    let fields: String = arg_types
+        .iter()
+        .map(|arg_type| match arg_type.required {
+            true => format!("  {}: {};\n", arg_type.name, arg_type.ts_type),
+            false => format!("  {}?: {} | null;\n", arg_type.name, arg_type.ts_type),
+        })
+        .collect();
+
+    Some(format!("export interface {}{} {{\n{}}}\n", interface_name, extends_str, fields))
+}
+
+// Report a story file that couldn't be written as a warning at `span`,
+// or as an error when `STORYBOOK_STRICT_WRITES=1`
+fn write_story_file_diagnostic(span: proc_macro2::Span, output_file: &std::path::Path, result: std::io::Result<()>) -> proc_macro2::TokenStream {
+    let Err(err) = result else {
+        return quote! {};
+    };
+    let message = format!("couldn't write story file {}: {}", output_file.display(), err);
+
+    if std::env::var("STORYBOOK_STRICT_WRITES").is_ok_and(|strict| strict == "1") {
+        return syn::Error::new(span, message).to_compile_error();
+    }
+    quote_spanned! {span=>
+        const _: () = {
+            #[deprecated(note = #message)]
+            struct StoryFileNotWritten;
+            let _ = StoryFileNotWritten;
+        };
+    }
+}
+
+// Write a story file from a `#[cfg]`-gated item, so only the variant for the enabled features is written
+#[doc(hidden)]
+#[proc_macro]
+pub fn __write_story_file(input: TokenStream) -> TokenStream {
+    let parser = syn::punctuated::Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated;
+    let args = parse_macro_input!(input with parser);
+    match args.iter().collect::<Vec<_>>().as_slice() {
+        [output_file, js_content] => {
+            let output_path = std::path::Path::new(&output_file.value()).to_path_buf();
+            let result = write_story_file(&output_path, &js_content.value());
+            write_story_file_diagnostic(output_file.span(), &output_path, result).into()
+        }
+        _ => TokenStream::new(),
+    }
+}
+
+// Resolve the directory that `.stories.js` files are written to
+// A struct-level `stories_dir` wins over `STORYBOOK_STORIES_DIR`, both relative to CARGO_MANIFEST_DIR
+// Next is `output_dir` from `storybook.toml`, relative to the config file
+// Without any of these, stories go to `storybook/stories` at the workspace root
+fn stories_output_dir(stories_dir: Option<&str>, config: Option<&LoadedConfig>) -> std::path::PathBuf {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").ok().map(std::path::PathBuf::from);
+    let custom_dir = stories_dir
+        .map(str::to_string)
+        .or_else(|| std::env::var("STORYBOOK_STORIES_DIR").ok());
+
+    if custom_dir.is_none() {
+        if let Some(config) = config {
+            if let Some(output_dir) = &config.config.stories.output_dir {
+                return config.dir().join(output_dir);
+            }
+        }
+    }
+
+    match (custom_dir, manifest_dir) {
+        (Some(custom_dir), Some(manifest_dir)) => manifest_dir.join(custom_dir),
+        (Some(custom_dir), None) => std::path::PathBuf::from(custom_dir),
+        (None, Some(manifest_dir)) => manifest_dir.parent().unwrap().join("storybook/stories"),
+        (None, None) => std::path::PathBuf::from("storybook/stories"),
+    }
+}
+
+/// Macro to load the workspace `storybook.toml` config
+///
+/// Validates the config at compile time and exposes it as constants in a
+/// `storybook_config` module. `#[derive(Story)]` reads the same file, so settings
+/// apply to every story without repeating them on each struct.
+///
+/// ```toml
+/// [stories]
+/// output_dir = "storybook/stories"
+/// csf_version = 3
+/// default_layout = "centered"
+/// ```
+///
+/// Usage: `storybook::storybook_config!();`
+#[proc_macro]
+pub fn storybook_config(_input: TokenStream) -> TokenStream {
+    let loaded = match config::load_config() {
+        Ok(loaded) => loaded,
+        Err(message) => {
+            return syn::Error::new(proc_macro2::Span::call_site(), message).to_compile_error().into()
+        }
+    };
+
+    // Include the file so edits to it trigger a rebuild
+    let track_file = loaded.as_ref().map(|loaded| {
+        let path = loaded.path.display().to_string();
+        quote! { const _: &str = include_str!(#path); }
+    });
+
+    let stories = loaded.as_ref().map(|loaded| &loaded.config.stories);
+    let output_dir = stories
+        .and_then(|stories| stories.output_dir.clone())
+        .unwrap_or_else(|| "storybook/stories".to_string());
+    let csf_version = stories.and_then(|stories| stories.csf_version).unwrap_or(2);
+    let default_layout = quote_option_str(&stories.and_then(|stories| stories.default_layout.clone()));
+    let error_boundary = stories.and_then(|stories| stories.error_boundary).unwrap_or(false);
+
+    let expanded = quote! {
+        #track_file
+
+        /// Settings loaded from `storybook.toml`
+        pub mod storybook_config {
+            /// Directory generated `.stories.js` files are written to
+            pub const OUTPUT_DIR: &str = #output_dir;
+            /// Component Story Format version of generated stories
+            pub const CSF_VERSION: u8 = #csf_version;
+            /// Storybook layout applied to stories without their own
+            pub const DEFAULT_LAYOUT: Option<&str> = #default_layout;
+            /// Whether stories show render errors in place instead of crashing
+            pub const ERROR_BOUNDARY: bool = #error_boundary;
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Attribute macro to document the dominator crate path being used.
+/// 
+/// This is a documentation/metadata attribute that doesn't affect generated code,
+/// but makes it clear which dominator path is being used when it's vendored or re-exported.
+/// 
+/// Usage on a module:
+/// ```ignore
+/// #[storybook::set_dominator_path("crate::vendored::dominator")]
+/// mod stories;
+/// ```
+/// 
+/// This allows specifying a custom path to the dominator crate when it's been vendored
+/// or re-exported from a different location.
+#[proc_macro_attribute]
+pub fn set_dominator_path(_args: TokenStream, input: TokenStream) -> TokenStream {
+    // This is just a marker attribute, it doesn't modify the input
+    // It's used for documentation purposes to indicate which dominator path is in use
+    input
+}
+
+#[proc_macro_derive(Story, attributes(story, dominator_crate))]
+pub fn derive_story(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    // Features named by `argtype_if`, in order of first use
+    let mut features: Vec<String> = Vec::new();
+    if let Data::Struct(data) = &input.data {
+        for field in &data.fields {
+            for (feature, _) in get_story_attrs(field).conditional_attrs {
+                if !features.contains(&feature) {
+                    features.push(feature);
+                }
+            }
+        }
+    }
+
+    if features.is_empty() {
+        return expand_story(&input, false).into();
+    }
+
+    // Expand the story once per combination of features, each gated by a matching `#[cfg]`
+    let mut expanded = proc_macro2::TokenStream::new();
+    for enabled_mask in 0..(1u32 << features.len()) {
+        let is_enabled = |index: usize| enabled_mask & (1 << index) != 0;
+        let enabled: Vec<&String> = features.iter().enumerate().filter(|(i, _)| is_enabled(*i)).map(|(_, f)| f).collect();
+        let disabled: Vec<&String> = features.iter().enumerate().filter(|(i, _)| !is_enabled(*i)).map(|(_, f)| f).collect();
+        let cfg: syn::Attribute = syn::parse_quote! {
+            #[cfg(all(#(feature = #enabled,)* #(not(feature = #disabled)),*))]
+        };
+
+        let variant_input = match apply_conditional_attrs(&input, &enabled) {
+            Ok(variant_input) => variant_input,
+            Err(err) => return err.to_compile_error().into(),
+        };
+        let variant = expand_story(&variant_input, true);
+        let mut file = match syn::parse2::<syn::File>(variant.clone()) {
+            Ok(file) => file,
+            // Errors are emitted as `compile_error!` tokens, which aren't items
+            Err(_) => return variant.into(),
+        };
+        for item in &mut file.items {
+            if let Some(attrs) = item_attrs_mut(item) {
+                attrs.insert(0, cfg.clone());
+            }
+        }
+        expanded.extend(quote! { #file });
+    }
+
+    expanded.into()
+}
+
+// Append each field's `argtype_if` overrides for the enabled features as a later `#[story(...)]`,
+// so they win over the field's own attributes
+fn apply_conditional_attrs(input: &DeriveInput, enabled: &[&String]) -> syn::Result<DeriveInput> {
+    let mut input = input.clone();
+    if let Data::Struct(data) = &mut input.data {
+        for field in data.fields.iter_mut() {
+            for (feature, then) in get_story_attrs(field).conditional_attrs {
+                if enabled.contains(&&feature) {
+                    let overrides = parse_conditional_overrides(&then)?;
+                    field.attrs.push(syn::parse_quote! { #[story(#(#overrides),*)] });
+                }
+            }
+        }
+    }
+    Ok(input)
+}
+
+// Parse `argtype_if`'s `then`, e.g. `control = 'select', from = 'Mode'`, into attribute tokens
+// Values are quoted with `'` so they fit inside the attribute's string, or with `\"` when they contain `'`
+fn parse_conditional_overrides(then: &str) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let mut entries = Vec::new();
+    let mut current = String::new();
+    let mut quote_char = None;
+    for c in then.chars() {
+        match (c, quote_char) {
+            ('\'' | '"', None) => quote_char = Some(c),
+            (c, Some(open)) if c == open => quote_char = None,
+            (',', None) => {
+                entries.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    entries.push(current);
+
+    entries
+        .iter()
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let invalid = || {
+                syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    format!("invalid argtype_if override '{}', expected key = 'value'", entry),
+                )
+            };
+            let Some((key, value)) = entry.split_once('=') else {
+                let key: syn::Ident = syn::parse_str(entry).map_err(|_| invalid())?;
+                return Ok(quote! { #key });
+            };
+            let key: syn::Ident = syn::parse_str(key.trim()).map_err(|_| invalid())?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('\'')
+                .and_then(|value| value.strip_suffix('\''))
+                .or_else(|| value.strip_prefix('"').and_then(|value| value.strip_suffix('"')))
+                .ok_or_else(invalid)?;
+            Ok(quote! { #key = #value })
+        })
+        .collect()
+}
+
+// Attributes of the item kinds a story expansion produces
+fn item_attrs_mut(item: &mut syn::Item) -> Option<&mut Vec<syn::Attribute>> {
+    match item {
+        syn::Item::Struct(item) => Some(&mut item.attrs),
+        syn::Item::Impl(item) => Some(&mut item.attrs),
+        syn::Item::Fn(item) => Some(&mut item.attrs),
+        syn::Item::Const(item) => Some(&mut item.attrs),
+        syn::Item::Static(item) => Some(&mut item.attrs),
+        syn::Item::Macro(item) => Some(&mut item.attrs),
+        syn::Item::Mod(item) => Some(&mut item.attrs),
+        syn::Item::Use(item) => Some(&mut item.attrs),
+        _ => None,
+    }
+}
+
+// Generate the story impls for a struct
+// With `defer_write` the `.stories.js` file is written by a `__write_story_file!` item instead of now
+fn expand_story(input: &DeriveInput, defer_write: bool) -> proc_macro2::TokenStream {
+    let dominator_crate = match syn::parse_str::<syn::Path>(&get_dominator_crate_attr(input)) {
+        Ok(path) => path,
+        Err(err) => return err.to_compile_error(),
+    };
+    let struct_attrs = match get_story_struct_attrs(input) {
+        Ok(struct_attrs) => struct_attrs,
+        Err(err) => return err.to_compile_error(),
+    };
+    let config = match config::load_config() {
+        Ok(config) => config,
+        Err(message) => return syn::Error::new(input.ident.span(), message).to_compile_error(),
+    };
+    let name = &input.ident;
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let name_str = name.to_string();
+    let story_args_name = syn::Ident::new(&format!("{}StoryArgs", name), name.span());
+    // Story names are exported as is from `.stories.js` files, like `meta::StoryMetaBuilder` checks
+    if !is_js_identifier(&name_str) {
+        return syn::Error::new(name.span(), format!("story name '{}' is not a JavaScript identifier", name_str)).to_compile_error();
+    }
+
+    // Extract field information
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => panic!("Story can only be derived for structs with named fields"),
+        },
+        _ => panic!("Story can only be derived for structs"),
+    };
+
+    let story_args_fields = fields.iter().filter_map(|field| {
+        let field_name = &field.ident;
+        let field_ty = &field.ty;
+        let field_attrs = get_story_attrs(field);
+        
+        // Skip fields marked with #[story(skip)]
+        if field_attrs.skip {
+            return None;
+        }
+        
+        // Make enum select fields optional so they can deserialize from undefined
+        let should_be_optional = field_attrs.is_enum_select(field_ty);
+
+        // Bool selects hold the selected label, converted back to a bool in the From impl
+        if field_attrs.bool_select_labels(field_ty).is_some() {
+            return Some(quote! {
+                #[serde(default)]
+                pub #field_name: Option<String>
+            });
+        }
+
+        // External enum options arrive as strings, parsed with the enum's FromStr impl in the From impl
+        if field_attrs.external_options.is_some() {
+            return Some(quote! {
+                #[serde(default)]
+                pub #field_name: Option<String>
+            });
+        }
+
+        // Switch values arrive as the mapped 'on'/'off' strings
+        if field_attrs.control_type.as_deref() == Some("switch") {
+            let switch_ty = field_attrs.from_type.unwrap_or_else(|| field_ty.clone());
+            return Some(quote! {
+                #[serde(default, deserialize_with = "storybook::deserialize_switch")]
+                pub #field_name: #switch_ty
+            });
+        }
+
+        let field_def = if let Some(from_type) = field_attrs.from_type {
+            if should_be_optional {
                 quote! {
                     #[serde(default)]
+                    pub #field_name: Option<#from_type>
+                }
+            } else {
+                quote! {
+                    #[serde(default)]
+                    pub #field_name: #from_type
+                }
+            }
+        } else {
+            if should_be_optional {
+                // Selects over `#[repr(u8)]` enums send the numeric discriminant
+                quote! {
+                    #[serde(default, deserialize_with = "storybook::deserialize_select")]
                     pub #field_name: Option<#field_ty>
                 }
             } else {
@@ -254,18 +1888,52 @@ pub fn derive_story(input: TokenStream) -> TokenStream {
         Some(field_def)
     });
 
+    // Check each arg type deserializes, so a missing impl is reported at the field rather than in StoryArgs
+    let deserialize_assertions = fields.iter().filter_map(|field| {
+        let field_attrs = get_story_attrs(field);
+        if field_attrs.skip || field_attrs.bool_select_labels(&field.ty).is_some() || field_attrs.external_options.is_some() {
+            return None;
+        }
+        let arg_ty = field_attrs.from_type.unwrap_or_else(|| field.ty.clone());
+        Some(quote_spanned! {arg_ty.span()=>
+            assert_deserialize::<#arg_ty>();
+        })
+    });
+
     let from_impl_fields = fields.iter().map(|field| {
         let field_name = &field.ident;
-        let (control_type, _, _, _, skip) = get_story_attrs(field);
+        let field_ty = &field.ty;
+        let field_attrs = get_story_attrs(field);
         
-        if skip {
+        if field_attrs.skip {
             // For skipped fields, use Default::default()
             return quote! { #field_name: Default::default() };
         }
         
-        let should_be_optional = control_type.as_ref().map(|c| c == "select").unwrap_or(false);
+        let should_be_optional = field_attrs.is_enum_select(field_ty);
         
-        if should_be_optional {
+        if let Some((true_label, _)) = field_attrs.bool_select_labels(field_ty) {
+            // Map the selected label back to a bool, leaving optional bools unset if nothing is selected
+            if quote!(#field_ty).to_string().starts_with("Option <") {
+                quote! { #field_name: value.#field_name.map(|label| label == #true_label) }
+            } else {
+                quote! { #field_name: value.#field_name.as_deref() == Some(#true_label) }
+            }
+        } else if let Some(external_options) = &field_attrs.external_options {
+            // Spanned at the field type, so a missing FromStr impl is reported there
+            if quote!(#field_ty).to_string().starts_with("Option <") {
+                quote_spanned! {field_ty.span()=>
+                    #field_name: value.#field_name.as_deref().and_then(|option| option.parse().ok())
+                }
+            } else {
+                quote_spanned! {field_ty.span()=>
+                    #field_name: storybook::parse_external_option(value.#field_name.as_deref(), &[#(#external_options),*])
+                }
+            }
+        } else if field_attrs.is_rgba_bytes() {
+            // Unparseable colors fall back to opaque black, like the default
+            quote! { #field_name: storybook::parse_rgba_string(&value.#field_name).unwrap_or([0, 0, 0, 255]) }
+        } else if should_be_optional {
             // For optional enum fields, unwrap_or_default() or just use the option as-is
             quote! { #field_name: value.#field_name.unwrap_or_default() }
         } else {
@@ -273,29 +1941,154 @@ pub fn derive_story(input: TokenStream) -> TokenStream {
         }
     });
 
-    // Generate arg type information for each field
-    let mut arg_types_for_js: Vec<(String, String, String, String, String)> = Vec::new();
-    let mut arg_types_vec = Vec::new();
-    
-    for field in fields.iter() {
-        let field_name = &field.ident;
-        let field_name_str = field_name.as_ref().unwrap().to_string();
-        let field_ty = &field.ty;
-        let ty_string = quote!(#field_ty).to_string();
-        let is_option = ty_string.starts_with("Option <");
+    // Generate arg type information for each field
+    let mut arg_types_for_js: Vec<JsArgType> = Vec::new();
+    let mut arg_types_vec = Vec::new();
+    let mut inline_previews = Vec::new();
+    let mut custom_controls = Vec::new();
+    let mut dependent_options_fns = Vec::new();
+    let mut const_options = Vec::new();
+    let mut external_options_list = Vec::new();
+    let mut options_fns = Vec::new();
+    let mut select_enums = Vec::new();
+    
+    for field in fields.iter() {
+        let field_name = &field.ident;
+        let field_name_str = field_name.as_ref().unwrap().to_string();
+        let field_ty = &field.ty;
+        let ty_string = quote!(#field_ty).to_string();
+        let is_option = ty_string.starts_with("Option <");
+
+        let field_attrs = get_story_attrs(field);
+        let bool_select_labels = field_attrs.bool_select_labels(field_ty);
+        let is_enum_select = field_attrs.is_enum_select(field_ty);
+        let is_rgba_bytes = field_attrs.is_rgba_bytes();
+        let StoryFieldAttrs { control_type, default_value, default_rust, from_type, lorem_count, skip, min, max, i18n_key, tooltip, option_groups, preview_fn, renderer, options_const, options_fn, rows, min_length, linked_to, aspect_ratio, number_format, display_format, currency, resize, depends_on, dependent_options, allow_custom_value, color_type, radio_icons, external_options, bool_labels, .. } = field_attrs;
+        
+        // Skip fields marked with #[story(skip)]
+        if skip {
+            continue;
+        }
+
+        if is_enum_select {
+            select_enums.push(quote! { <#field_ty as storybook::StorySelect>::register(); });
+        }
+
+        // Typed-in values must deserialize, so the field has to be a string select rather than an enum or bool
+        let is_string_select = control_type.as_deref() == Some("select")
+            && !is_enum_select
+            && bool_select_labels.is_none()
+            && external_options.is_none();
+        if allow_custom_value && !is_string_select {
+            return syn::Error::new_spanned(
+                field,
+                "`allow_custom_value` needs a `String` field with `control = \"select\"` and `options_const`, `options_fn` or `dependent_options`",
+            )
+            .to_compile_error();
+        }
+
+        if options_fn.is_some() && control_type.as_deref() != Some("select") {
+            return syn::Error::new_spanned(field, "`options_fn` needs `control = \"select\"`").to_compile_error();
+        }
+
+        if let Some(external_options) = &external_options {
+            if control_type.as_deref() != Some("select") {
+                return syn::Error::new_spanned(field, "`external_options` needs `control = \"select\"`").to_compile_error();
+            }
+            if external_options.is_empty() {
+                return syn::Error::new_spanned(field, "`external_options` needs at least one option, e.g. \"Error,Warn,Info\"")
+                    .to_compile_error();
+            }
+        }
+
+        // `custom:Name` controls are components registered in preview.js
+        let custom_component = control_type.as_deref().and_then(|control_type| control_type.strip_prefix("custom:"));
+        if let Some(component) = custom_component {
+            if !is_js_identifier(component) {
+                return syn::Error::new_spanned(
+                    field,
+                    format!("invalid custom control '{}', expected a component name such as \"custom:GradientPicker\"", component),
+                )
+                .to_compile_error();
+            }
+        }
+
+        if let Some(color_type) = &color_type {
+            if color_type.value() != "rgba_bytes" {
+                return syn::Error::new(color_type.span(), "unknown `color_type`, expected \"rgba_bytes\"").to_compile_error();
+            }
+            if control_type.as_deref() != Some("color") || ty_string.replace(" ", "") != "[u8;4]" {
+                return syn::Error::new(color_type.span(), "`color_type = \"rgba_bytes\"` needs a `[u8; 4]` field with `control = \"color\"`")
+                    .to_compile_error();
+            }
+        }
 
-        let (control_type, default_value, from_type, lorem_count, skip) = get_story_attrs(field);
-        
-        // Skip fields marked with #[story(skip)]
-        if skip {
-            continue;
+        if let Some(Err(err)) = bool_labels.as_ref().map(split_bool_labels) {
+            return err.to_compile_error();
         }
 
+        let resize = match resize.as_ref().map(validate_resize).transpose() {
+            Ok(resize) => resize,
+            Err(err) => return err.to_compile_error(),
+        };
+
+        // Controls are picked from the type the story args deserialize into
+        let ty_to_check = if let Some(from_type) = &from_type {
+            quote!(#from_type).to_string()
+        } else {
+            ty_string.clone()
+        };
+
         let mut options = quote! { None };
         let mut options_json = String::new();
         let control = if let Some(ref control_type) = control_type {
             match control_type.as_str() {
                 "color" => quote! { storybook::ControlType::Color },
+                "switch" => quote! { storybook::ControlType::Switch },
+                "json" => quote! { storybook::ControlType::Object },
+                "range" => quote! { storybook::ControlType::Range },
+                "number" => quote! { storybook::ControlType::Number },
+                "inline" => quote! { storybook::ControlType::Inline },
+                "custom" => quote! { storybook::ControlType::Custom },
+                _ if custom_component.is_some() => quote! { storybook::ControlType::Custom },
+                "select" if bool_select_labels.is_some() => {
+                    let (true_label, false_label) = bool_select_labels.as_ref().unwrap();
+                    options = quote! { Some(vec![#true_label.to_string(), #false_label.to_string()]) };
+                    options_json = format!("[{}, {}]", js_string_literal(true_label), js_string_literal(false_label));
+                    quote! { storybook::ControlType::Select }
+                }
+                // Options are computed from the current args by `get_dependent_options`
+                "select" if dependent_options.is_some() => {
+                    let dependent_options = dependent_options.as_ref().unwrap();
+                    dependent_options_fns.push(quote! {
+                        options.insert(#field_name_str.to_string(), #dependent_options as storybook::DependentOptionsFn);
+                    });
+                    quote! { storybook::ControlType::Select }
+                }
+                // Options are given inline, as enums from other crates can't derive `StorySelect`
+                "select" if external_options.is_some() => {
+                    let external_options = external_options.as_ref().unwrap();
+                    options = quote! { Some(vec![#(#external_options.to_string()),*]) };
+                    let options_js: Vec<String> = external_options.iter().map(|option| js_string_literal(option)).collect();
+                    options_json = format!("[{}]", options_js.join(", "));
+                    external_options_list.push(quote! { (#field_name_str, &[#(#external_options),*] as &'static [&'static str]) });
+                    quote! { storybook::ControlType::Select }
+                }
+                // Options are computed once, when the story is registered, and read back from the enum registry
+                "select" if options_fn.is_some() => {
+                    let options_fn = options_fn.as_ref().unwrap();
+                    options = quote! { Some(#options_fn()) };
+                    options_json = format!("get_enum_options('{}::{}')", name_str, field_name_str);
+                    options_fns.push(quote! { (#field_name_str, #options_fn as storybook::OptionsFn) });
+                    quote! { storybook::ControlType::Select }
+                }
+                "select" if options_const.is_some() => {
+                    let options_const = options_const.as_ref().unwrap();
+                    options = quote! { Some(#options_const.iter().map(|option| option.to_string()).collect()) };
+                    options_json = format!("get_const_options('{}', '{}')", name_str, field_name_str);
+                    const_options.push(quote! { (#field_name_str, #options_const) });
+                    quote! { storybook::ControlType::Select }
+                }
                 "select" => {
                     options = quote! { Some(<#field_ty as storybook::StorySelect>::options()) };
                     // Extract the enum type name from the field type
@@ -303,35 +2096,53 @@ pub fn derive_story(input: TokenStream) -> TokenStream {
                     options_json = format!("get_enum_options('{}')", enum_type_name);
                     quote! { storybook::ControlType::Select }
                 }
+                "inline-radio" if is_enum_select => {
+                    options = quote! { Some(<#field_ty as storybook::StorySelect>::options()) };
+                    options_json = format!("get_enum_options('{}')", ty_string.trim().replace(" ", ""));
+                    quote! { storybook::ControlType::InlineRadio }
+                }
                 _ => quote! { storybook::ControlType::Text },
             }
         } else {
-            let ty_to_check = if let Some(from_type) = &from_type {
-                quote!(#from_type).to_string()
-            } else {
-                ty_string.clone()
-            };
-
             if ty_to_check.contains("bool") {
                 quote! { storybook::ControlType::Boolean }
-            } else if ty_to_check.contains("i32")
-                || ty_to_check.contains("f32")
-                || ty_to_check.contains("u32")
-                || ty_to_check.contains("f64")
-                || ty_to_check.contains("usize")
-            {
+            } else if is_numeric_type(&ty_to_check) {
                 quote! { storybook::ControlType::Number }
             } else {
                 quote! { storybook::ControlType::Text }
             }
         };
 
-        let default_value_quoted = match &default_value {
-            Some(v) => quote! { Some(#v.to_string()) },
-            None => {
-                if let Some(lorem_word_count) = lorem_count {
+        // Rust defaults must have the type the args deserialize into
+        let default_rust_ty = match &from_type {
+            Some(from_type) => quote! { #from_type },
+            None => quote! { #field_ty },
+        };
+        let default_value_quoted = match (&default_rust, &default_value) {
+            (Some(expr), _) => quote_spanned! {expr.span()=>
+                Some({
+                    let value: #default_rust_ty = #expr;
+                    storybook::__js_default(&value)
+                })
+            },
+            (None, Some(v)) => quote! { Some(#v.to_string()) },
+            (None, None) => {
+                if is_rgba_bytes {
+                    quote! { Some(#RGBA_BYTES_DEFAULT.to_string()) }
+                } else if let Some(lorem_word_count) = lorem_count {
                     let lorem_text = generate_lorem_ipsum(lorem_word_count);
                     quote! { Some(#lorem_text.to_string()) }
+                } else if is_enum_select {
+                    quote! { Some(<#field_ty as storybook::StorySelect>::default_option().to_string()) }
+                } else if let Some(options_const) = &options_const {
+                    quote! { #options_const.first().map(|option| option.to_string()) }
+                } else if let Some(options_fn) = &options_fn {
+                    quote! { #options_fn().into_iter().next() }
+                } else if let (Some(external_options), false) = (&external_options, is_option) {
+                    let first_option = &external_options[0];
+                    quote! { Some(#first_option.to_string()) }
+                } else if control_type.as_deref() == Some("json") {
+                    quote! { Some("{}".to_string()) }
                 } else {
                     quote! { None }
                 }
@@ -342,94 +2153,718 @@ pub fn derive_story(input: TokenStream) -> TokenStream {
             Some(ct) => {
                 match ct.as_str() {
                     "color" => "color".to_string(),
+                    "switch" => "switch".to_string(),
+                    "json" => "json".to_string(),
+                    "range" => "range".to_string(),
+                    "number" => "number".to_string(),
+                    "inline" => "inline".to_string(),
+                    "custom" => "custom".to_string(),
+                    _ if custom_component.is_some() => "custom".to_string(),
                     "select" => "select".to_string(),
+                    "inline-radio" if is_enum_select => "inline-radio".to_string(),
                     _ => "text".to_string(),
                 }
             }
             None => {
-                if ty_string.contains("bool") {
+                if ty_to_check.contains("bool") {
                     "boolean".to_string()
-                } else if ty_string.contains("i32") || ty_string.contains("f32") || ty_string.contains("u32") || ty_string.contains("f64") || ty_string.contains("usize") {
+                } else if is_numeric_type(&ty_to_check) {
                     "number".to_string()
                 } else {
                     "text".to_string()
                 }
             }
-        };
-        
-        let default_val_str = match &default_value {
-            Some(dv) => dv.clone(),
-            None => {
-                if let Some(lorem_word_count) = lorem_count {
-                    // Generate lorem ipsum text
-                    format!("'{}'", generate_lorem_ipsum(lorem_word_count))
-                } else if control_str == "select" {
-                    "null".to_string()
-                } else if ty_string.contains("String") {
-                    "''".to_string()
-                } else if ty_string.contains("bool") {
-                    "false".to_string()
-                } else if ty_string.contains("i32") || ty_string.contains("f32") || ty_string.contains("u32") || ty_string.contains("f64") || ty_string.contains("usize") {
-                    "0".to_string()
-                } else {
-                    "undefined".to_string()
-                }
+        };
+        
+        let is_percentage = display_format.as_deref() == Some("percentage");
+        if let Some(display_format) = &display_format {
+            if !is_percentage {
+                return syn::Error::new_spanned(field, format!("unknown display_format '{}', expected \"percentage\"", display_format))
+                    .to_compile_error();
+            }
+            if control_str != "number" && control_str != "range" {
+                return syn::Error::new_spanned(field, "`display_format` needs a number or range control").to_compile_error();
+            }
+        }
+
+        let default_val_str = match (&default_rust, &default_value) {
+            // The value of a Rust expression is only known at runtime, so it's read back from the WASM
+            (Some(_), _) => format!("get_rust_default('{}', '{}')", name_str, field_name_str),
+            (None, Some(dv)) => dv.clone(),
+            (None, None) => {
+                if is_rgba_bytes {
+                    RGBA_BYTES_DEFAULT.to_string()
+                } else if let Some(lorem_word_count) = lorem_count {
+                    // Generate lorem ipsum text
+                    format!("'{}'", generate_lorem_ipsum(lorem_word_count))
+                } else if let (Some((_, false_label)), false) = (&bool_select_labels, is_option) {
+                    js_string_literal(false_label)
+                } else if is_enum_select {
+                    format!("get_enum_default('{}')", ty_string.trim().replace(" ", ""))
+                } else if options_const.is_some() {
+                    format!("get_const_options('{}', '{}')[0]", name_str, field_name_str)
+                } else if options_fn.is_some() {
+                    format!("get_enum_options('{}::{}')[0]", name_str, field_name_str)
+                } else if let (Some(external_options), false) = (&external_options, is_option) {
+                    js_string_literal(&external_options[0])
+                } else if control_str == "json" {
+                    "{}".to_string()
+                } else if control_str == "select" {
+                    "null".to_string()
+                } else if ty_string.contains("String") {
+                    "''".to_string()
+                } else if ty_string.contains("bool") {
+                    "false".to_string()
+                } else if is_numeric_type(&ty_to_check) {
+                    "0".to_string()
+                } else {
+                    "undefined".to_string()
+                }
+            }
+        };
+        // Percentages show the default as 0-100, like the control
+        let default_val_str = match default_val_str.parse::<f64>() {
+            Ok(default) if is_percentage => format!("{}", (default * 100.0 * 1e6).round() / 1e6),
+            Err(_) if is_percentage && default_val_str != "null" && default_val_str != "undefined" => format!("({}) * 100", default_val_str),
+            _ => default_val_str,
+        };
+        
+        let mut js_extra = Vec::new();
+        let js_control = match control_str.as_str() {
+            // Switches are boolean controls whose values are mapped to on/off
+            "switch" => {
+                js_extra.push("mapping: { true: 'on', false: 'off' }".to_string());
+                "{ type: 'boolean' }".to_string()
+            }
+            // JSON values are edited with Storybook's object editor
+            "json" => "{ type: 'object' }".to_string(),
+            // Inline previews are edited as text, with the preview rendered by `render_inline_preview`
+            "inline" => {
+                js_extra.push("inlinePreview: true".to_string());
+                "{ type: 'text' }".to_string()
+            }
+            // Control components registered in preview.js are passed through by name
+            "custom" if custom_component.is_some() => {
+                format!("{{ type: 'custom', component: {} }}", js_string_literal(custom_component.unwrap()))
+            }
+            // Custom controls are mounted by addons calling `get_custom_control_element`
+            "custom" => {
+                js_extra.push("customControl: true".to_string());
+                "{ type: 'object' }".to_string()
+            }
+            // Optional text that must not be empty when given; null still passes
+            "text" if min_length.is_some() => format!("{{ type: 'text', minLength: {} }}", min_length.unwrap()),
+            // Percentages are edited as 0-100 and divided by 100 before rendering
+            "number" | "range" if is_percentage => format!("{{ type: '{}', min: 0, max: 100, step: 1 }}", control_str),
+            // Bounded numbers limit the input or slider range
+            "number" | "range" if min.is_some() || max.is_some() => {
+                let mut control = format!("{{ type: '{}'", control_str);
+                if let Some(min) = min {
+                    control.push_str(&format!(", min: {}", min));
+                }
+                if let Some(max) = max {
+                    control.push_str(&format!(", max: {}", max));
+                }
+                control.push_str(" }");
+                control
+            }
+            // Comboboxes are text inputs suggesting the options, rendered by an addon reading `allowCustomValue`
+            "select" if allow_custom_value => {
+                js_extra.push("allowCustomValue: true".to_string());
+                "{ type: 'text' }".to_string()
+            }
+            // Enum options are discriminants for `#[repr(u8)]` enums, labelled with the variant names
+            "select" if is_enum_select => {
+                // Grouped options are labelled with their group, as select controls have no optgroups
+                let group_labels: String = option_groups
+                    .iter()
+                    .flatten()
+                    .flat_map(|(group_name, options)| {
+                        options.iter().map(move |option| {
+                            format!(", {}: {}", js_string_literal(option), js_string_literal(&format!("{} / {}", group_name, option)))
+                        })
+                    })
+                    .collect();
+                format!(
+                    "{{ type: 'select', labels: {{ ...get_enum_labels('{}'){} }} }}",
+                    ty_string.trim().replace(" ", ""),
+                    group_labels
+                )
+            }
+            // Radio buttons are labelled with their icons, falling back to the variant names
+            "inline-radio" => {
+                let enum_type_name = ty_string.trim().replace(" ", "");
+                match &radio_icons {
+                    Some(icons) => {
+                        let icons: Vec<String> = icons.iter().map(|icon| js_string_literal(icon)).collect();
+                        format!(
+                            "{{ type: 'inline-radio', labels: Object.fromEntries(get_enum_options('{name}').map((option, i) => [option, [{icons}][i] ?? get_enum_labels('{name}')[option]])) }}",
+                            name = enum_type_name,
+                            icons = icons.join(", ")
+                        )
+                    }
+                    None => format!("{{ type: 'inline-radio', labels: {{ ...get_enum_labels('{}') }} }}", enum_type_name),
+                }
+            }
+            other => format!("'{}'", other),
+        };
+        
+        if let (Some("inline"), Some(preview_fn)) = (control_type.as_deref(), &preview_fn) {
+            inline_previews.push(quote! {
+                previews.insert(#field_name_str.to_string(), #preview_fn as storybook::InlinePreviewFn);
+            });
+        }
+
+        if let (Some("custom"), Some(renderer)) = (control_type.as_deref(), &renderer) {
+            custom_controls.push(quote! {
+                controls.insert(
+                    #field_name_str.to_string(),
+                    <#renderer as storybook::controls::InlineControlRenderer>::render_control as storybook::controls::CustomControlFn,
+                );
+            });
+        }
+
+        // Textarea sizing is passed through to the text control's input element
+        if rows.is_some() || resize.is_some() {
+            let mut component_props = Vec::new();
+            if let Some(rows) = rows {
+                component_props.push(format!("rows: {}", rows));
+            }
+            if let Some(resize) = &resize {
+                component_props.push(format!("style: 'resize: {}'", resize));
+            }
+            js_extra.push(format!("componentProps: {{ {} }}", component_props.join(", ")));
+        }
+        let textarea_rows_quoted = match rows {
+            Some(rows) => quote! { Some(#rows) },
+            None => quote! { None },
+        };
+        let textarea_resize_quoted = quote_option_str(&resize);
+        // Currency amounts are formatted with `Intl.NumberFormat`, in US dollars unless a currency is given
+        let is_number_control = control_str == "number" || control_str == "range";
+        let (number_format, currency) = match number_format.as_deref() {
+            Some("currency") if is_number_control => {
+                let currency = currency.clone().unwrap_or_else(|| "USD".to_string());
+                js_extra.push(format!("numberFormat: {{ style: 'currency', currency: {} }}", js_string_literal(&currency)));
+                (Some("currency".to_string()), Some(currency))
+            }
+            _ => (None, None),
+        };
+        let number_format_quoted = match &number_format {
+            Some(number_format) => quote! { Some(#number_format.to_string()) },
+            None => quote! { None },
+        };
+        let number_currency_quoted = match &currency {
+            Some(currency) => quote! { Some(#currency.to_string()) },
+            None => quote! { None },
+        };
+        let display_format_quoted = match &display_format {
+            Some(display_format) => quote! { Some(#display_format.to_string()) },
+            None => quote! { None },
+        };
+        // Linked numbers keep their aspect ratio through `get_linked_control_value`, 1:1 unless a ratio is given
+        let linked_field = linked_to
+            .as_ref()
+            .filter(|_| is_number_control)
+            .map(|linked_to| (linked_to.clone(), aspect_ratio.unwrap_or(1.0)));
+        let linked_field_quoted = match &linked_field {
+            Some((linked_to, ratio)) => {
+                js_extra.push(format!("linkedTo: {{ field: {}, ratio: {} }}", js_string_literal(linked_to), ratio));
+                quote! { Some((#linked_to.to_string(), #ratio)) }
+            }
+            None => quote! { None },
+        };
+        let min_length_quoted = match min_length {
+            Some(min_length) => quote! { Some(#min_length) },
+            None => quote! { None },
+        };
+
+        // Tooltips and translation keys are shown on the arg's type in the docs table, like `ArgType::type_detail`
+        let mut js_table = Vec::new();
+        let type_detail: Vec<String> = tooltip
+            .iter()
+            .cloned()
+            .chain(i18n_key.iter().map(|i18n_key| format!("i18n: {}", i18n_key)))
+            .collect();
+        if !type_detail.is_empty() {
+            js_table.push(format!("type: {{ detail: {} }}", js_text_literal(&type_detail.join("\n"))));
+        }
+        let i18n_key_quoted = match &i18n_key {
+            Some(i18n_key) => quote! { Some(#i18n_key.to_string()) },
+            None => quote! { None },
+        };
+        let tooltip_quoted = match &tooltip {
+            Some(tooltip) => quote! { Some(#tooltip.to_string()) },
+            None => quote! { None },
+        };
+
+        let option_groups_quoted = match &option_groups {
+            Some(groups) => {
+                let groups = groups.iter().map(|(group_name, options)| {
+                    quote! { (#group_name.to_string(), vec![#(#options.to_string()),*]) }
+                });
+                quote! { Some(vec![#(#groups),*]) }
+            }
+            None => quote! { None },
+        };
+
+        let min_quoted = match min {
+            Some(min) => quote! { Some(#min) },
+            None => quote! { None },
+        };
+        let max_quoted = match max {
+            Some(max) => quote! { Some(#max) },
+            None => quote! { None },
+        };
+
+        // Selects with fixed options take one of them; other selects are strings, or discriminants for `#[repr]` enums
+        let ts_type = match (&bool_select_labels, &external_options) {
+            (Some((true_label, false_label)), _) => format!("{} | {}", js_string_literal(true_label), js_string_literal(false_label)),
+            (_, Some(external_options)) => external_options.iter().map(|option| js_string_literal(option)).collect::<Vec<_>>().join(" | "),
+            _ => match control_str.as_str() {
+                "boolean" | "switch" => "boolean".to_string(),
+                "number" | "range" => "number".to_string(),
+                "select" | "inline-radio" if is_enum_select => "string | number".to_string(),
+                "json" | "custom" => "unknown".to_string(),
+                _ => "string".to_string(),
+            },
+        };
+
+        arg_types_for_js.push(JsArgType {
+            name: field_name_str.clone(),
+            control: js_control,
+            default_value: default_val_str,
+            required: !is_option,
+            options: if options_json.is_empty() { None } else { Some(options_json) },
+            extra: js_extra,
+            table: js_table,
+            depends_on: depends_on.clone(),
+            dependent_options: dependent_options.is_some() && control_type.as_deref() == Some("select"),
+            percentage: is_percentage,
+            ts_type,
+        });
+
+        let radio_icons_quoted = match &radio_icons {
+            Some(icons) if control_str == "inline-radio" => quote! { Some(vec![#(#icons.to_string()),*]) },
+            _ => quote! { None },
+        };
+        let custom_control_quoted = match custom_component {
+            Some(component) => quote! { Some(#component.to_string()) },
+            None => quote! { None },
+        };
+
+        arg_types_vec.push(quote! {
+            storybook::ArgType {
+                name: #field_name_str.to_string(),
+                default_value: #default_value_quoted,
+                control: #control,
+                required: !#is_option,
+                options: #options,
+                description: None,
+                min: #min_quoted,
+                max: #max_quoted,
+                i18n_key: #i18n_key_quoted,
+                tooltip: #tooltip_quoted,
+                option_groups: #option_groups_quoted,
+                textarea_rows: #textarea_rows_quoted,
+                textarea_resize: #textarea_resize_quoted,
+                min_length: #min_length_quoted,
+                linked_field: #linked_field_quoted,
+                number_format: #number_format_quoted,
+                number_currency: #number_currency_quoted,
+                display_format: #display_format_quoted,
+                custom_control: #custom_control_quoted,
+                radio_icons: #radio_icons_quoted,
+                allow_custom_value: #allow_custom_value,
+                depends_on: vec![#(#depends_on.to_string()),*],
+            }
+        });
+    }
+
+    // Dependent selects start with the options for the default values of the args they depend on
+    let js_defaults: std::collections::HashMap<String, String> = arg_types_for_js
+        .iter()
+        .map(|arg_type| (arg_type.name.clone(), arg_type.default_value.clone()))
+        .collect();
+    for arg_type in arg_types_for_js.iter_mut().filter(|arg_type| arg_type.dependent_options) {
+        let context: Vec<String> = arg_type
+            .depends_on
+            .iter()
+            .map(|field| format!("{}: {}", field, js_defaults.get(field).map(String::as_str).unwrap_or("undefined")))
+            .collect();
+        let options = format!("get_dependent_options('{}', '{}', {{ {} }})", name_str, arg_type.name, context.join(", "));
+        if arg_type.default_value == "null" {
+            arg_type.default_value = format!("{}[0]", options);
+        }
+        arg_type.options = Some(options);
+    }
+
+    // Generate the Storybook JavaScript file
+    let (output_file, template) = match generate_storybook_js(&name_str, fields, &arg_types_for_js, &struct_attrs, config.as_ref()) {
+        Ok(story_file) => story_file,
+        Err(message) => return syn::Error::new(name.span(), message).to_compile_error(),
+    };
+    let js_content = template.render();
+    let story_template = story_template_tokens(&template);
+    // Live controls keep the `Mutable` fields of the rendered story and set them from new args
+    let live_overrides = if struct_attrs.reactive {
+        if struct_attrs.live_controls {
+            return syn::Error::new(name.span(), "`reactive` already updates every field in place, so it can't be combined with `live_controls`")
+                .to_compile_error();
+        }
+        quote! {
+            fn render_to_dom(args: wasm_bindgen::JsValue, container: &web_sys::Element)
+            where
+                Self: storybook::Story,
+            {
+                storybook::reactive::__render_reactive::<Self>(args, container)
+            }
+
+            fn live_update_fn() -> Option<storybook::LiveUpdateFn> {
+                let update: storybook::LiveUpdateFn = |handles, args| {
+                    storybook::__update_live::<Self, storybook::reactive::ReactiveStoryArgs<Self>>(handles, args, |reactive, story| {
+                        reactive.set(story)
+                    })
+                };
+                Some(update)
+            }
+        }
+    } else if struct_attrs.live_controls {
+        let live = live_fields(fields);
+        if live.is_empty() {
+            return syn::Error::new(name.span(), "live_controls needs at least one Mutable field").to_compile_error();
+        }
+        let live_names: Vec<_> = live.iter().map(|field| &field.ident).collect();
+        let live_tys: Vec<_> = live.iter().map(|field| &field.ty).collect();
+        let live_indices: Vec<syn::Index> = (0..live.len()).map(syn::Index::from).collect();
+        quote! {
+            fn live_handles(&self) -> Option<Box<dyn std::any::Any>> {
+                Some(Box::new((#(self.#live_names.clone(),)*)))
+            }
+
+            fn live_update_fn() -> Option<storybook::LiveUpdateFn> {
+                let update: storybook::LiveUpdateFn = |handles, args| {
+                    storybook::__update_live::<Self, (#(#live_tys,)*)>(handles, args, |handles, story| {
+                        #(handles.#live_indices.set(story.#live_names.get_cloned());)*
+                    })
+                };
+                Some(update)
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Reactive stories hold each field in a `Mutable`, and render through `ReactiveStory`
+    let reactive_items = if struct_attrs.reactive {
+        let vis = &input.vis;
+        let reactive_name = syn::Ident::new(&format!("{}Reactive", name), name.span());
+        let field_names: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+        let field_tys: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+        quote! {
+            /// Fields of the story, each in a `Mutable` that changes when Storybook updates the arg
+            #[derive(Clone)]
+            #vis struct #reactive_name {
+                #(pub #field_names: storybook::reactive::Mutable<#field_tys>,)*
+            }
+
+            impl storybook::reactive::Reactive for #name {
+                type Fields = #reactive_name;
+
+                fn to_fields(&self) -> #reactive_name {
+                    #reactive_name {
+                        #(#field_names: storybook::reactive::Mutable::new(self.#field_names.clone()),)*
+                    }
+                }
+
+                fn set_fields(&self, fields: &#reactive_name) {
+                    #(fields.#field_names.set_neq(self.#field_names.clone());)*
+                }
+            }
+
+            impl storybook::Story for #name {
+                fn to_story(self) -> #dominator_crate::Dom {
+                    <Self as storybook::reactive::ReactiveStory>::to_reactive_story(storybook::reactive::ReactiveStoryArgs::new(self))
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Direct entry point for JS callers that don't go through `render_story`
+    let wasm_export = match &struct_attrs.wasm_export_name {
+        Some(export_name) => quote! {
+            #[wasm_bindgen::prelude::wasm_bindgen]
+            pub fn #export_name(args: wasm_bindgen::JsValue) -> Result<web_sys::Node, wasm_bindgen::JsValue> {
+                storybook::render_story_direct::<#name>(args)
+            }
+        },
+        None => quote! {},
+    };
+
+    // Custom viewports can be added in preview.js, so unknown presets only warn
+    let viewport_warning = match &struct_attrs.default_viewport {
+        Some(viewport) if !VIEWPORT_PRESETS.contains(&viewport_preset_key(&viewport.value()).as_str()) => {
+            let message = format!(
+                "`{}` is not one of Storybook's viewport presets; make sure it is added to the viewport options in preview.js",
+                viewport.value()
+            );
+            quote_spanned! {viewport.span()=>
+                const _: () = {
+                    #[deprecated(note = #message)]
+                    struct UnknownViewport;
+                    let _ = UnknownViewport;
+                };
+            }
+        }
+        _ => quote! {},
+    };
+
+    let write_story_file_item = if defer_write {
+        let output_file = output_file.to_string_lossy();
+        quote! { storybook::__write_story_file!(#output_file, #js_content); }
+    } else {
+        write_story_file_diagnostic(name.span(), &output_file, write_story_file(&output_file, &js_content))
+    };
+
+    // Declarations sit next to the story file, e.g. `Button.stories.d.ts`
+    let write_types_file_item = match generate_story_types(&name_str, &arg_types_for_js, &struct_attrs) {
+        Some(types_content) => {
+            let types_file = output_file.with_extension("d.ts");
+            if defer_write {
+                let types_file = types_file.to_string_lossy();
+                quote! { storybook::__write_story_file!(#types_file, #types_content); }
+            } else {
+                write_story_file_diagnostic(name.span(), &types_file, write_story_file(&types_file, &types_content))
+            }
+        }
+        None => quote! {},
+    };
+
+    let canvas_width = quote_option_str(&struct_attrs.canvas_width);
+    let canvas_padding = quote_option_str(&struct_attrs.canvas_padding);
+    let default_viewport = quote_option_str(&struct_attrs.default_viewport.as_ref().map(|viewport| viewport_preset_key(&viewport.value())));
+    let canvas_height = quote_option_str(&struct_attrs.canvas_height);
+    let component_id = quote_option_str(&struct_attrs.component_id);
+    let title = story_title(&name_str, &struct_attrs);
+    let primary_variant_name = struct_attrs.component_story_name.as_deref().unwrap_or("Default");
+    let component_docs = quote_option_str(&struct_attrs.component_docs);
+    let aria_label = quote_option_str(&struct_attrs.aria_label);
+    let note = quote_option_str(&struct_attrs.note);
+    let feature_flags = &struct_attrs.feature_flags;
+    let decorators = &struct_attrs.decorators;
+    let dependencies = &struct_attrs.dependencies;
+    let shortcut_keys = struct_attrs.keyboard_shortcuts.iter().map(|(key, _)| key);
+    let shortcut_actions = struct_attrs.keyboard_shortcuts.iter().map(|(_, action)| action);
+    let responsive_preview = struct_attrs.responsive_preview;
+    let render_count = struct_attrs.render_count;
+    let screenshot = struct_attrs.screenshot;
+    let render_once = struct_attrs.render_once;
+    let portal_target = quote_option_str(&struct_attrs.portal_target);
+    let arg_table_columns = &struct_attrs.arg_table_columns;
+    let controls_expanded = match struct_attrs.controls_expanded {
+        Some(expanded) => quote! { Some(#expanded) },
+        None => quote! { None },
+    };
+    let error_boundary = struct_attrs.error_boundary
+        || config
+            .as_ref()
+            .and_then(|loaded| loaded.config.stories.error_boundary)
+            .unwrap_or(false);
+    let interact_delay_ms = match struct_attrs.interact_delay_ms {
+        Some(delay_ms) => quote! { Some(#delay_ms) },
+        None => quote! { None },
+    };
+
+    // Generate helper methods
+    let expanded = quote! {
+        const _: () = {
+            fn assert_deserialize<T: serde::de::DeserializeOwned>() {}
+            #[allow(dead_code)]
+            fn assert_args_deserialize() {
+                #(#deserialize_assertions)*
+            }
+        };
+
+        #[derive(serde::Deserialize, Default)]
+        pub struct #story_args_name {
+            #(#story_args_fields),*
+        }
+
+        impl From<#story_args_name> for #name {
+            fn from(value: #story_args_name) -> Self {
+                Self {
+                    #(#from_impl_fields),*
+                }
+            }
+        }
+
+        impl #impl_generics storybook::StoryMeta for #name #ty_generics #where_clause {
+            type StoryArgs = #story_args_name;
+
+            fn name() -> &'static str {
+                #name_str
+            }
+
+            fn args() -> Vec<storybook::ArgType> {
+                vec![
+                    #(#arg_types_vec),*
+                ]
+            }
+
+            fn canvas_width() -> Option<&'static str> {
+                #canvas_width
+            }
+
+            fn canvas_padding() -> Option<&'static str> {
+                #canvas_padding
+            }
+
+            fn default_viewport() -> Option<&'static str> {
+                #default_viewport
+            }
+
+            fn canvas_height() -> Option<&'static str> {
+                #canvas_height
+            }
+
+            fn title() -> String {
+                #title.to_string()
+            }
+
+            fn story_template() -> Option<storybook::template::StoryTemplate> {
+                Some(#story_template)
+            }
+
+            fn component_id() -> Option<&'static str> {
+                #component_id
+            }
+
+            fn primary_variant_name() -> &'static str {
+                #primary_variant_name
+            }
+
+            fn interact_delay_ms() -> Option<u32> {
+                #interact_delay_ms
+            }
+
+            fn component_docs() -> Option<&'static str> {
+                #component_docs
+            }
+
+            fn note() -> Option<&'static str> {
+                #note
+            }
+
+            fn source_location() -> Option<&'static str> {
+                Some(concat!(file!(), ":", line!()))
+            }
+
+            fn aria_label() -> Option<&'static str> {
+                #aria_label
+            }
+
+            fn error_boundary() -> bool {
+                #error_boundary
+            }
+
+            fn responsive_preview() -> bool {
+                #responsive_preview
+            }
+
+            fn track_renders() -> bool {
+                #render_count
+            }
+
+            fn render_once() -> bool {
+                #render_once
+            }
+
+            fn portal_target() -> Option<&'static str> {
+                #portal_target
+            }
+
+            fn screenshot() -> bool {
+                #screenshot
+            }
+
+            fn controls_expanded() -> Option<bool> {
+                #controls_expanded
+            }
+
+            fn arg_table_columns() -> &'static [&'static str] {
+                &[#(#arg_table_columns),*]
+            }
+
+            fn feature_flags() -> &'static [&'static str] {
+                &[#(#feature_flags),*]
+            }
+
+            fn decorators() -> &'static [&'static str] {
+                &[#(#decorators),*]
+            }
+
+            fn dependencies() -> &'static [&'static str] {
+                &[#(#dependencies),*]
+            }
+
+            fn keyboard_shortcuts() -> &'static [(&'static str, &'static str)] {
+                &[#((#shortcut_keys, #shortcut_actions)),*]
             }
-        };
-        
-        arg_types_for_js.push((
-            field_name_str.clone(),
-            control_str,
-            default_val_str,
-            if is_option { "false" } else { "true" }.to_string(),
-            options_json,
-        ));
 
-        arg_types_vec.push(quote! {
-            storybook::ArgType {
-                name: #field_name_str.to_string(),
-                default_value: #default_value_quoted,
-                control: #control,
-                required: !#is_option,
-                options: #options,
+            fn const_options() -> Vec<(&'static str, &'static [&'static str])> {
+                vec![#(#const_options),*]
             }
-        });
-    }
 
-    // Generate the Storybook JavaScript file
-    generate_storybook_js(&name_str, fields, &arg_types_for_js);
+            fn external_options() -> Vec<(&'static str, &'static [&'static str])> {
+                vec![#(#external_options_list),*]
+            }
 
-    // Generate helper methods
-    let expanded = quote! {
-        #[derive(serde::Deserialize, Default)]
-        pub struct #story_args_name {
-            #(#story_args_fields),*
-        }
+            fn options_fns() -> Vec<(&'static str, storybook::OptionsFn)> {
+                vec![#(#options_fns),*]
+            }
 
-        impl From<#story_args_name> for #name {
-            fn from(value: #story_args_name) -> Self {
-                Self {
-                    #(#from_impl_fields),*
-                }
+            fn register_select_enums() {
+                #(#select_enums)*
             }
-        }
 
-        impl #impl_generics storybook::StoryMeta for #name #ty_generics #where_clause {
-            type StoryArgs = #story_args_name;
+            fn dependent_options() -> std::collections::HashMap<String, storybook::DependentOptionsFn> {
+                #[allow(unused_mut)]
+                let mut options = std::collections::HashMap::new();
+                #(#dependent_options_fns)*
+                options
+            }
 
-            fn name() -> &'static str {
-                #name_str
+            #live_overrides
+
+            fn inline_previews() -> std::collections::HashMap<String, storybook::InlinePreviewFn> {
+                #[allow(unused_mut)]
+                let mut previews = std::collections::HashMap::new();
+                #(#inline_previews)*
+                previews
             }
 
-            fn args() -> Vec<storybook::ArgType> {
-                vec![
-                    #(#arg_types_vec),*
-                ]
+            fn custom_controls() -> std::collections::HashMap<String, storybook::controls::CustomControlFn> {
+                #[allow(unused_mut)]
+                let mut controls = std::collections::HashMap::new();
+                #(#custom_controls)*
+                controls
             }
         }
+
+        #wasm_export
+
+        #reactive_items
+
+        #viewport_warning
+
+        #write_story_file_item
+
+        #write_types_file_item
     };
 
-    TokenStream::from(expanded)
+    expanded
 }
 
 /// Derive macro for StorySelect trait
@@ -437,7 +2872,7 @@ pub fn derive_story(input: TokenStream) -> TokenStream {
 /// This macro generates select control options from an enum.
 /// Each variant becomes an option in a select dropdown in Storybook.
 /// Also implements FromStr for deserializing from Storybook values.
-#[proc_macro_derive(StorySelect, attributes(story_select))]
+#[proc_macro_derive(StorySelect, attributes(story_select, story))]
 pub fn derive_story_select(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -450,10 +2885,21 @@ pub fn derive_story_select(input: TokenStream) -> TokenStream {
         _ => panic!("StorySelect can only be derived for enums"),
     };
 
+    // Option values match the names serde deserializes, so `#[serde(rename)]` is respected
+    let rename_all = get_serde_rename(&input.attrs, "rename_all");
+    let variant_option = |variant: &syn::Variant| {
+        get_serde_rename(&variant.attrs, "rename").unwrap_or_else(|| {
+            let variant_str = variant.ident.to_string();
+            match &rename_all {
+                Some(rule) => apply_rename_rule(&variant_str, rule),
+                None => variant_str,
+            }
+        })
+    };
+
     // Generate option values from enum variants
     let options = variants.iter().map(|variant| {
-        let variant_name = &variant.ident;
-        let variant_str = variant_name.to_string();
+        let variant_str = variant_option(variant);
         
         quote! {
             #variant_str.to_string()
@@ -463,7 +2909,7 @@ pub fn derive_story_select(input: TokenStream) -> TokenStream {
     // Generate FromStr match arms
     let from_str_arms = variants.iter().map(|variant| {
         let variant_name = &variant.ident;
-        let variant_str = variant_name.to_string();
+        let variant_str = variant_option(variant);
         
         quote! {
             #variant_str => Ok(#name::#variant_name)
@@ -473,20 +2919,168 @@ pub fn derive_story_select(input: TokenStream) -> TokenStream {
     // Generate Display match arms
     let display_arms = variants.iter().map(|variant| {
         let variant_name = &variant.ident;
-        let variant_str = variant_name.to_string();
+        let variant_str = variant_option(variant);
         
         quote! {
             #name::#variant_name => #variant_str
         }
     });
 
+    // The `#[default]` variant is the initial select value, falling back to the first variant
+    let default_option = variants
+        .iter()
+        .find(|variant| variant.attrs.iter().any(|attr| attr.path().is_ident("default")))
+        .or_else(|| variants.first())
+        .map(variant_option)
+        .unwrap_or_default();
+
     let name_str = name.to_string();
 
+    // `#[story_select(use_qualified_name)]` registers the enum under its full path, e.g.
+    // `example::components::button::ButtonSize`, so enums sharing a name don't collide
+    let mut use_qualified_name = false;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("story_select")) {
+        let result = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("use_qualified_name") {
+                use_qualified_name = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown story_select attribute"))
+            }
+        });
+        if let Err(err) = result {
+            return err.to_compile_error().into();
+        }
+    }
+    let type_name = if use_qualified_name {
+        quote! { concat!(module_path!(), "::", #name_str) }
+    } else {
+        quote! { #name_str }
+    };
+
+    // `#[story(derive_default_from_first_variant)]` opts into a `Default` impl
+    let mut derive_default = false;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("story")) {
+        let result = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("derive_default_from_first_variant") {
+                derive_default = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown story attribute"))
+            }
+        });
+        if let Err(err) = result {
+            return err.to_compile_error().into();
+        }
+    }
+
+    let default_impl = if derive_default {
+        // `#[derive(Default)]` on an enum needs a `#[default]` variant, so that gives it away
+        let derives_default = variants
+            .iter()
+            .any(|variant| variant.attrs.iter().any(|attr| attr.path().is_ident("default")));
+        if derives_default {
+            return syn::Error::new_spanned(
+                name,
+                format!("{} already derives Default, remove derive_default_from_first_variant", name),
+            )
+            .to_compile_error()
+            .into();
+        }
+
+        let Some(first_variant) = variants.first().map(|variant| &variant.ident) else {
+            return syn::Error::new_spanned(name, "derive_default_from_first_variant needs at least one variant")
+                .to_compile_error()
+                .into();
+        };
+        quote! {
+            impl #impl_generics Default for #name #ty_generics #where_clause {
+                fn default() -> Self {
+                    #name::#first_variant
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // `#[story_select(group = "...")]` puts variants under a heading, in the order groups first appear
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for variant in variants {
+        for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("story_select")) {
+            let result = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("group") {
+                    let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                    let group_name = lit_str.value().trim().to_string();
+                    if group_name.is_empty() {
+                        return Err(syn::Error::new(lit_str.span(), "group must not be empty"));
+                    }
+                    match groups.iter_mut().find(|(name, _)| *name == group_name) {
+                        Some((_, options)) => options.push(variant_option(variant)),
+                        None => groups.push((group_name, vec![variant_option(variant)])),
+                    }
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown story_select attribute"))
+                }
+            });
+            if let Err(err) = result {
+                return err.to_compile_error().into();
+            }
+        }
+    }
+
+    let grouped_options_fn = if groups.is_empty() {
+        quote! {}
+    } else {
+        let group_names = groups.iter().map(|(group_name, _)| group_name);
+        let group_options = groups.iter().map(|(_, options)| quote! { vec![#(#options),*] });
+        quote! {
+            fn grouped_options() -> Option<Vec<(&'static str, Vec<&'static str>)>> {
+                Some(vec![#((#group_names, #group_options)),*])
+            }
+        }
+    };
+
+    // `#[repr(u8)]`-style enums are selected by their numeric discriminant
+    let is_repr_int = input.attrs.iter().any(|attr| {
+        let mut is_int = false;
+        if attr.path().is_ident("repr") {
+            let _ = attr.parse_nested_meta(|meta| {
+                if ["u8", "u16", "u32"].iter().any(|int| meta.path.is_ident(int)) {
+                    is_int = true;
+                }
+                Ok(())
+            });
+        }
+        is_int
+    });
+
+    let discriminant_fns = if is_repr_int {
+        let variant_names: Vec<_> = variants.iter().map(|variant| &variant.ident).collect();
+        quote! {
+            fn discriminants() -> Option<Vec<i64>> {
+                Some(vec![#(#name::#variant_names as i64),*])
+            }
+
+            fn from_discriminant(value: i64) -> Option<Self> {
+                #(
+                    if value == #name::#variant_names as i64 {
+                        return Some(#name::#variant_names);
+                    }
+                )*
+                None
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     // Generate implementation
     let expanded = quote! {
         impl #impl_generics storybook::StorySelect for #name #ty_generics #where_clause {
             fn type_name() -> &'static str {
-                #name_str
+                #type_name
             }
 
             fn options() -> Vec<String> {
@@ -494,16 +3088,27 @@ pub fn derive_story_select(input: TokenStream) -> TokenStream {
                     #(#options),*
                 ]
             }
+
+            fn default_option() -> &'static str {
+                #default_option
+            }
+
+            #grouped_options_fn
+
+            #discriminant_fns
+
+            // Registered once, by the first story using the enum or `init_enums()`
+            fn register() {
+                static REGISTERED: storybook::__Lazy<()> = storybook::__Lazy::new(storybook::register_select_enum::<#name>);
+                storybook::__Lazy::force(&REGISTERED);
+            }
         }
 
-        // Auto-register enum options on first use
+        // Kept for `register_enums!`, registering the enum if no story has yet
         impl #impl_generics #name #ty_generics #where_clause {
             #[doc(hidden)]
             pub fn __register_enum_options() {
-                storybook::register_enum_options(
-                    #name_str,
-                    <#name as storybook::StorySelect>::options()
-                );
+                <#name as storybook::StorySelect>::register();
             }
         }
 
@@ -518,6 +3123,25 @@ pub fn derive_story_select(input: TokenStream) -> TokenStream {
             }
         }
 
+        impl #impl_generics std::convert::TryFrom<&str> for #name #ty_generics #where_clause {
+            type Error = String;
+
+            // Spelled out, as `Self::Error` is ambiguous for enums with an `Error` variant
+            fn try_from(s: &str) -> Result<Self, String> {
+                <Self as std::str::FromStr>::from_str(s)
+            }
+        }
+
+        impl #impl_generics std::convert::TryFrom<String> for #name #ty_generics #where_clause {
+            type Error = String;
+
+            fn try_from(s: String) -> Result<Self, String> {
+                <Self as std::str::FromStr>::from_str(&s)
+            }
+        }
+
+        #default_impl
+
         impl #impl_generics std::fmt::Display for #name #ty_generics #where_clause {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 let s = match self {
@@ -531,6 +3155,261 @@ pub fn derive_story_select(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+// Helper to read `#[serde(rename = "...")]` or `#[serde(rename_all = "...")]`
+// For `rename(serialize = "...", deserialize = "...")` the deserialize name is used
+fn get_serde_rename(attrs: &[syn::Attribute], key: &str) -> Option<String> {
+    let mut rename = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(key) {
+                if let Ok(value) = meta.value() {
+                    rename = Some(value.parse::<syn::LitStr>()?.value());
+                } else {
+                    meta.parse_nested_meta(|inner| {
+                        let lit_str = inner.value()?.parse::<syn::LitStr>()?;
+                        if inner.path.is_ident("deserialize") {
+                            rename = Some(lit_str.value());
+                        }
+                        Ok(())
+                    })?;
+                }
+            } else if let Ok(value) = meta.value() {
+                // Skip the values of other serde attributes
+                let _ = value.parse::<syn::Expr>();
+            }
+            Ok(())
+        });
+    }
+    rename
+}
+
+// Helper to rename a PascalCase variant the way serde's `rename_all` does
+fn apply_rename_rule(variant: &str, rule: &str) -> String {
+    let snake_case = || {
+        let mut snake = String::new();
+        for (index, c) in variant.char_indices() {
+            if c.is_uppercase() && index > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        }
+        snake
+    };
+
+    match rule {
+        "lowercase" => variant.to_ascii_lowercase(),
+        "UPPERCASE" => variant.to_ascii_uppercase(),
+        "camelCase" => {
+            let mut chars = variant.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        }
+        "snake_case" => snake_case(),
+        "SCREAMING_SNAKE_CASE" => snake_case().to_ascii_uppercase(),
+        "kebab-case" => snake_case().replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake_case().replace('_', "-").to_ascii_uppercase(),
+        _ => variant.to_string(),
+    }
+}
+
+/// Derive macro for AriaMetadata trait
+///
+/// Documents the ARIA semantics of a story component for Storybook's a11y addon.
+/// `#[aria(...)]` can be placed on the struct or on any of its fields:
+/// - `role = "button"` - ARIA role of the root element
+/// - `aria_label = "Close"` - Static accessible name
+/// - `aria_label_from = "label"` - Field whose value is the accessible name
+/// - `aria_describedby = "hint-id"` - ID of the describing element
+#[proc_macro_derive(AriaMetadata, attributes(aria))]
+pub fn derive_aria_metadata(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => panic!("AriaMetadata can only be derived for structs with named fields"),
+        },
+        _ => panic!("AriaMetadata can only be derived for structs"),
+    };
+
+    let field_names: Vec<String> = fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref().map(|ident| ident.to_string()))
+        .collect();
+
+    let mut role = None;
+    let mut aria_label = None;
+    let mut aria_label_from = None;
+    let mut aria_describedby = None;
+
+    let aria_attrs = input
+        .attrs
+        .iter()
+        .chain(fields.iter().flat_map(|field| field.attrs.iter()))
+        .filter(|attr| attr.path().is_ident("aria"));
+
+    for attr in aria_attrs {
+        let result = attr.parse_nested_meta(|meta| {
+            let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+            if meta.path.is_ident("role") {
+                role = Some(lit_str.value());
+            } else if meta.path.is_ident("aria_label") {
+                aria_label = Some(lit_str.value());
+            } else if meta.path.is_ident("aria_label_from") {
+                if !field_names.contains(&lit_str.value()) {
+                    return Err(syn::Error::new(
+                        lit_str.span(),
+                        format!("no field named '{}' to take the aria label from", lit_str.value()),
+                    ));
+                }
+                aria_label_from = Some(lit_str.value());
+            } else if meta.path.is_ident("aria_describedby") {
+                aria_describedby = Some(lit_str.value());
+            } else {
+                return Err(meta.error("unknown aria attribute"));
+            }
+            Ok(())
+        });
+
+        if let Err(err) = result {
+            return err.to_compile_error().into();
+        }
+    }
+
+    let role = quote_option_str(&role);
+    let aria_label = quote_option_str(&aria_label);
+    let aria_label_from = quote_option_str(&aria_label_from);
+    let aria_describedby = quote_option_str(&aria_describedby);
+
+    let expanded = quote! {
+        impl #impl_generics storybook::accessibility::AriaMetadata for #name #ty_generics #where_clause {
+            fn aria_metadata() -> storybook::accessibility::AriaRegistration {
+                storybook::accessibility::AriaRegistration {
+                    role: #role,
+                    aria_label: #aria_label,
+                    aria_label_from: #aria_label_from,
+                    aria_describedby: #aria_describedby,
+                }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Derive macro for a story showing other stories side by side
+///
+/// `#[compose(stories = "Button::Default, Button::Disabled, Card")]` on a unit struct renders
+/// each listed story in a row, with the default args of the named variant (or the story's
+/// primary variant). Register the struct with `register_stories!` like any other story.
+#[proc_macro_derive(StoryCompose, attributes(compose, dominator_crate))]
+pub fn derive_story_compose(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let story_args_name = syn::Ident::new(&format!("{}StoryArgs", name), name.span());
+    // Story names are exported as is from `.stories.js` files, like `meta::StoryMetaBuilder` checks
+    if !is_js_identifier(&name_str) {
+        return syn::Error::new(name.span(), format!("story name '{}' is not a JavaScript identifier", name_str)).to_compile_error().into();
+    }
+
+    if !matches!(&input.data, Data::Struct(data) if matches!(data.fields, Fields::Unit)) {
+        return syn::Error::new(name.span(), "StoryCompose can only be derived for unit structs")
+            .to_compile_error()
+            .into();
+    }
+    let dominator_crate = match syn::parse_str::<syn::Path>(&get_dominator_crate_attr(&input)) {
+        Ok(path) => path,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let mut stories = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("compose")) {
+        let result = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("stories") {
+                let lit_str = meta.value()?.parse::<syn::LitStr>()?;
+                stories = Some(parse_composed_stories(&lit_str)?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown compose attribute"))
+            }
+        });
+        if let Err(err) = result {
+            return err.to_compile_error().into();
+        }
+    }
+    let Some(stories) = stories else {
+        return syn::Error::new(name.span(), "StoryCompose needs `#[compose(stories = \"...\")]`")
+            .to_compile_error()
+            .into();
+    };
+
+    // The composed story has no args of its own, so its story file only renders it
+    let config = match config::load_config() {
+        Ok(config) => config,
+        Err(message) => return syn::Error::new(name.span(), message).to_compile_error().into(),
+    };
+    let (output_file, template) =
+        match generate_storybook_js(&name_str, &Default::default(), &[], &StoryStructAttrs::default(), config.as_ref()) {
+            Ok(story_file) => story_file,
+            Err(message) => return syn::Error::new(name.span(), message).to_compile_error().into(),
+        };
+    let js_content = template.render();
+    let story_template = story_template_tokens(&template);
+    let write_diagnostic = write_story_file_diagnostic(name.span(), &output_file, write_story_file(&output_file, &js_content));
+
+    let composed = stories.iter().map(|(story, variant)| {
+        let variant = quote_option_str(variant);
+        quote! { storybook::compose::ComposeRegistration { story: #story, variant: #variant } }
+    });
+
+    let expanded = quote! {
+        #[derive(serde::Deserialize, Default)]
+        pub struct #story_args_name {}
+
+        impl From<#story_args_name> for #name {
+            fn from(_: #story_args_name) -> Self {
+                Self
+            }
+        }
+
+        impl storybook::StoryMeta for #name {
+            type StoryArgs = #story_args_name;
+
+            fn name() -> &'static str {
+                #name_str
+            }
+
+            fn args() -> Vec<storybook::ArgType> {
+                Vec::new()
+            }
+
+            fn composed_stories() -> &'static [storybook::compose::ComposeRegistration] {
+                &[#(#composed),*]
+            }
+
+            fn story_template() -> Option<storybook::template::StoryTemplate> {
+                Some(#story_template)
+            }
+        }
+
+        impl storybook::Story for #name {
+            fn to_story(self) -> #dominator_crate::Dom {
+                storybook::compose::render_composed(<Self as storybook::StoryMeta>::composed_stories())
+            }
+        }
+
+        #write_diagnostic
+    };
+
+    TokenStream::from(expanded)
+}
+
 /// Macro to generate a registration function for all stories
 /// Usage: register_stories!(Button, Card, Input);
 #[proc_macro]
@@ -574,3 +3453,39 @@ pub fn register_enums(input: TokenStream) -> TokenStream {
     
     TokenStream::from(expanded)
 }
+
+/// Macro to generate a registration function for story ARIA metadata
+/// Usage: register_aria!(Button, Input);
+#[proc_macro]
+pub fn register_aria(input: TokenStream) -> TokenStream {
+    let types = syn::parse_macro_input!(input with syn::punctuated::Punctuated::<syn::Type, syn::Token![,]>::parse_terminated);
+    
+    let registrations = types.iter().map(|ty| {
+        quote! {
+            storybook::accessibility::register_aria::<#ty>();
+        }
+    });
+    
+    let expanded = quote! {
+        #[wasm_bindgen::prelude::wasm_bindgen]
+        pub fn init_aria() {
+            #(#registrations)*
+        }
+    };
+    
+    TokenStream::from(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_storybook_versions_to_csf_versions() {
+        assert_eq!(csf_version_for("6"), Some(2));
+        assert_eq!(csf_version_for("7"), Some(3));
+        assert_eq!(csf_version_for(" 8 "), Some(3));
+        assert_eq!(csf_version_for("10"), None);
+        assert_eq!(csf_version_for("7.6.17"), None);
+    }
+}
@@ -0,0 +1,412 @@
+//! The `.stories.js` file template, shared by `#[derive(Story)]`, which writes the files while
+//! compiling, and `storybook-codegen`, which writes them from the story registry at runtime, so
+//! both produce the same files.
+
+/// WASM package the stories import, relative to the directory the `.stories.js` files are written to
+pub const DEFAULT_WASM_MODULE: &str = "../../example/pkg/example.js";
+
+/// Everything a story's `.stories.js` file is rendered from
+///
+/// Fields holding JS are written to the file as they are, so they must already be quoted,
+/// e.g. with `js_string_literal`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoryTemplate {
+    /// Name the story is registered and rendered under
+    pub name: String,
+    /// Module the WASM package is imported from
+    pub wasm_module: String,
+    pub title: String,
+    /// Storybook component ID, overriding the one derived from the title
+    pub component_id: Option<String>,
+    /// Decorators by name, which must be in scope globally, e.g. set up in preview.js
+    pub decorators: Vec<String>,
+    /// Tag the story for `generate_story_screenshots_script`
+    pub screenshot: bool,
+    pub arg_types: Vec<TemplateArgType>,
+    /// Entries of the default export's `parameters`, as JS `key: value`
+    pub parameters: Vec<String>,
+    /// Viewport preset selected through `globals`, for Storybook 9+
+    pub viewport: Option<String>,
+    /// ID of the element the story is mounted into instead of its container, without the `#`
+    pub portal_target: Option<String>,
+    /// Fields pushed into the rendered story with `update_story_arg` instead of re-rendering it
+    pub live_fields: Option<Vec<String>>,
+    /// Mark and measure each render in the DevTools performance panel
+    pub measure_performance: bool,
+    /// Export name of the story's main variant
+    pub export_name: String,
+    /// Write the story as a CSF3 object rather than a CSF2 bound template
+    pub csf3: bool,
+    /// Helpers imported from `storybook/test` for the play steps
+    pub test_imports: Vec<String>,
+    /// Statements of the main variant's play function
+    pub play_steps: Vec<String>,
+    /// Interval in ms of the `AutoPlay` story cycling through the variants
+    pub auto_play: Option<u32>,
+    /// Export listed first in the sidebar, which Storybook opens by default
+    pub default_variant: Option<String>,
+}
+
+/// One entry of a story's `argTypes`, along with its default arg
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateArgType {
+    pub name: String,
+    /// Storybook control, as JS
+    pub control: String,
+    /// Description, as JS
+    pub description: String,
+    /// Select options, as a JS expression
+    pub options: Option<String>,
+    /// Entries of `table`, as JS `key: value`
+    pub table: Vec<String>,
+    /// Other entries of the arg type, as JS `key: value`
+    pub extra: Vec<String>,
+    /// Default arg, as a JS expression, left out of `args` if `None`
+    pub default_value: Option<String>,
+    /// Edited as 0-100 and divided by 100 before rendering
+    pub percentage: bool,
+    /// Select whose options come from `get_dependent_options`
+    pub dependent_options: bool,
+}
+
+impl StoryTemplate {
+    /// Template for a story with no args or settings, importing the default WASM module
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            wasm_module: DEFAULT_WASM_MODULE.to_string(),
+            title: format!("Components/{}", name),
+            name,
+            component_id: None,
+            decorators: Vec::new(),
+            screenshot: false,
+            arg_types: Vec::new(),
+            parameters: Vec::new(),
+            viewport: None,
+            portal_target: None,
+            live_fields: None,
+            measure_performance: false,
+            export_name: "Default".to_string(),
+            csf3: false,
+            test_imports: Vec::new(),
+            play_steps: Vec::new(),
+            auto_play: None,
+            default_variant: None,
+        }
+    }
+
+    /// Render the `.stories.js` file
+    pub fn render(&self) -> String {
+        let name = &self.name;
+        let export = &self.export_name;
+
+        let mut imports_str = if self.test_imports.is_empty() {
+            String::new()
+        } else {
+            format!("import {{ {} }} from 'storybook/test';\n", self.test_imports.join(", "))
+        };
+
+        let id_str = match &self.component_id {
+            Some(component_id) => format!("  id: {},\n", js_string_literal(component_id)),
+            None => String::new(),
+        };
+
+        let decorators_str = if self.decorators.is_empty() {
+            String::new()
+        } else {
+            format!("  decorators: [{}],\n", self.decorators.join(", "))
+        };
+
+        // Tagged stories are picked out of Storybook's index by the `generate_story_screenshots_script` script
+        let tags_str = if self.screenshot { "  tags: ['screenshot'],\n" } else { "" };
+
+        let args_str = self.arg_types.iter().map(TemplateArgType::render).collect::<Vec<_>>().join(",\n");
+
+        let parameters_str = if self.parameters.is_empty() {
+            String::new()
+        } else {
+            format!("  parameters: {{\n    {}\n  }},\n", self.parameters.join(",\n    "))
+        };
+
+        // Storybook 9+ selects the viewport through globals rather than `parameters.viewport`
+        let globals_str = match &self.viewport {
+            Some(viewport) => format!("  globals: {{\n    viewport: {{ value: {}, isRotated: false }}\n  }},\n", js_string_literal(viewport)),
+            None => String::new(),
+        };
+
+        // Percentages are shown as 0-100 but rendered from 0.0-1.0, so they're converted before anything reads the args
+        let percentage_str: String = self
+            .arg_types
+            .iter()
+            .filter(|arg_type| arg_type.percentage)
+            .map(|arg_type| format!("  args = {{ ...args, {field}: args.{field} == null ? args.{field} : args.{field} / 100 }};\n", field = arg_type.name))
+            .collect();
+
+        // Dependent selects fall back to their first option when the args they depend on change
+        let dependent_options_str: String = self
+            .arg_types
+            .iter()
+            .filter(|arg_type| arg_type.dependent_options)
+            .map(|arg_type| {
+                format!(
+                    "  const {field}Options = get_dependent_options('{name}', '{field}', args);\n  if (!{field}Options.includes(args.{field})) args = {{ ...args, {field}: {field}Options[0] }};\n",
+                    field = arg_type.name,
+                    name = name
+                )
+            })
+            .collect();
+
+        // Live stories push arg changes into the rendered story's `Mutable`s instead of re-rendering,
+        // as long as only those fields and not the globals changed
+        let (live_prelude, live_update_str, live_record_str) = match &self.live_fields {
+            Some(live_fields) => {
+                let fields: Vec<String> = live_fields.iter().map(|field| js_string_literal(field)).collect();
+                (
+                    format!("const liveFields = [{}];\nlet live = null;\n\n", fields.join(", ")),
+                    format!(
+                        "  const onlyLiveChanged = live && JSON.stringify(globals) === live.globals\n    && Object.keys({{ ...live.args, ...args }}).every((key) => liveFields.includes(key) || args[key] === live.args[key]);\n  if (onlyLiveChanged && update_story_arg('{}', args)) {{\n    live.args = args;\n    return live.container;\n  }}\n",
+                        name
+                    ),
+                    "  live = { container, args, globals: JSON.stringify(globals) };\n".to_string(),
+                )
+            }
+            None => (String::new(), String::new(), String::new()),
+        };
+
+        // Portal stories are mounted into their target, which is created up front so the story's
+        // own code can find it, and leave the container Storybook shows empty
+        let (portal_prelude, render_str) = match &self.portal_target {
+            Some(portal_target) => (
+                format!(
+                    "if (!document.getElementById({id})) {{\n  const portal = document.createElement('div');\n  portal.id = {id};\n  document.body.appendChild(portal);\n}}\n\n",
+                    id = js_string_literal(portal_target)
+                ),
+                format!("  render_story_with_globals('{}', args, globals);\n", name),
+            ),
+            None => (
+                String::new(),
+                format!("  const dom = render_story_with_globals('{}', args, globals);\n  container.appendChild(dom);\n", name),
+            ),
+        };
+
+        // Render timings show up in the DevTools performance panel and `get_performance_entries`
+        let (perf_start_str, perf_end_str) = if self.measure_performance {
+            (
+                format!("  performance.mark('storybook-render-start-{}');\n", name),
+                format!(
+                    "  performance.mark('storybook-render-end-{name}');\n  performance.measure('storybook-render-{name}', 'storybook-render-start-{name}', 'storybook-render-end-{name}');\n",
+                    name = name
+                ),
+            )
+        } else {
+            (String::new(), String::new())
+        };
+
+        // CSF3 stories are objects with a render function, CSF2 stories are bound template functions
+        let default_story_str = if self.csf3 { "{ render: Template }" } else { "Template.bind({})" };
+
+        let default_args_str = self
+            .arg_types
+            .iter()
+            .filter_map(|arg_type| Some(format!("  {}: {}", arg_type.name, arg_type.default_value.as_ref()?)))
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        let play_str = if self.play_steps.is_empty() {
+            String::new()
+        } else {
+            format!("\n{}.play = async ({{ canvasElement, args }}) => {{\n  {}\n}};\n", export, self.play_steps.join("\n  "))
+        };
+
+        // The `AutoPlay` story steps Storybook's args through the variants, with a button to pause it.
+        // It stops once another story is selected, as play functions have no teardown.
+        let auto_play_str = match self.auto_play {
+            Some(interval) => {
+                imports_str.push_str("import { addons } from 'storybook/preview-api';\n");
+                format!(
+                    r#"
+export const AutoPlay = {default_story};
+AutoPlay.args = {{ ...{export}.args }};
+AutoPlay.play = async ({{ id }}) => {{
+  const variants = [{export}.args, ...get_story_variants('{name}').map((variant) => ({{ ...{export}.args, ...variant.args }}))];
+  const channel = addons.getChannel();
+  let index = 0;
+  let paused = false;
+
+  document.getElementById('storybook-auto-play')?.remove();
+  const button = document.createElement('button');
+  button.id = 'storybook-auto-play';
+  button.textContent = 'Pause';
+  Object.assign(button.style, {{ position: 'fixed', right: '8px', bottom: '8px', zIndex: '1000' }});
+  button.addEventListener('click', () => {{
+    paused = !paused;
+    button.textContent = paused ? 'Resume' : 'Pause';
+  }});
+  document.body.appendChild(button);
+
+  const timer = setInterval(() => {{
+    if (new URLSearchParams(window.location.search).get('id') !== id) {{
+      clearInterval(timer);
+      button.remove();
+      return;
+    }}
+    if (paused) return;
+    index = (index + 1) % variants.length;
+    channel.emit('updateStoryArgs', {{ storyId: id, updatedArgs: variants[index] }});
+  }}, {interval});
+}};
+"#,
+                    default_story = default_story_str,
+                    export = export,
+                    name = name,
+                    interval = interval
+                )
+            }
+            None => String::new(),
+        };
+
+        // Storybook lists a file's stories, and opens the first of them, in `__namedExportsOrder`
+        let exports_order_str = match &self.default_variant {
+            Some(default_variant) => {
+                let exports = std::iter::once(export.as_str())
+                    .chain(self.auto_play.map(|_| "AutoPlay"))
+                    .filter(|other| other != default_variant)
+                    .map(js_string_literal);
+                let order: Vec<String> = std::iter::once(js_string_literal(default_variant)).chain(exports).collect();
+                format!("\nexport const __namedExportsOrder = [{}];\n", order.join(", "))
+            }
+            None => String::new(),
+        };
+
+        format!(r#"{}import init, {{ register_all_stories, render_story_with_globals, update_story_arg, get_enum_options, get_enum_labels, get_enum_default, get_const_options, get_dependent_options, get_rust_default, get_story_variants, init_enums }} from {};
+
+// Initialize WASM
+await init();
+
+console.log('About to call init_enums...');
+init_enums();
+console.log('init_enums called');
+
+register_all_stories();
+
+// Define the story with populated enum options
+export default {{
+{}  title: {},
+{}{}  argTypes: {{
+{}
+  }},
+{}{}}};
+
+{}{}const Template = (args, {{ globals }}) => {{
+{}{}{}  const container = document.createElement('div');
+{}{}{}{}  return container;
+}};
+
+export const {} = {};
+{}.args = {{
+{}
+}};
+{}{}{}"#, imports_str, js_string_literal(&self.wasm_module), id_str, js_string_literal(&self.title), decorators_str, tags_str, args_str, parameters_str, globals_str, portal_prelude, live_prelude, percentage_str, dependent_options_str, live_update_str, perf_start_str, render_str, perf_end_str, live_record_str, export, default_story_str, export, default_args_str, play_str, auto_play_str, exports_order_str)
+    }
+}
+
+impl TemplateArgType {
+    // The arg's entry in `argTypes`
+    fn render(&self) -> String {
+        let options_str = match &self.options {
+            Some(options) => format!(", options: {}", options),
+            None => String::new(),
+        };
+        let table_str = if self.table.is_empty() {
+            String::new()
+        } else {
+            format!(", table: {{ {} }}", self.table.join(", "))
+        };
+        let extra_str: String = self.extra.iter().map(|extra| format!(", {}", extra)).collect();
+
+        format!(
+            "    {}: {{\n      control: {},\n      description: {}{}{}{}\n    }}",
+            self.name, self.control, self.description, options_str, table_str, extra_str
+        )
+    }
+}
+
+/// Quote a string as a single-quoted JS string literal
+pub fn js_string_literal(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('\'', "\\'")
+        .replace('\n', "\\n");
+    format!("'{}'", escaped)
+}
+
+/// Quote a string as a JS template literal, keeping embedded newlines readable
+pub fn js_template_literal(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace("${", "\\${");
+    format!("`{}`", escaped)
+}
+
+/// Quote text as a JS string, using a template literal when it spans multiple lines
+pub fn js_text_literal(value: &str) -> String {
+    if value.contains('\n') {
+        js_template_literal(value)
+    } else {
+        js_string_literal(value)
+    }
+}
+
+/// Whether a name can be used as a JS identifier, ASCII only
+///
+/// Story names are exported as is from `.stories.js` files, so both `#[derive(Story)]` and
+/// `meta::StoryMetaBuilder` check them with this.
+pub fn is_js_identifier(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_only_ascii_js_identifiers() {
+        assert!(is_js_identifier("Button"));
+        assert!(is_js_identifier("_$story2"));
+        assert!(!is_js_identifier("2Button"));
+        assert!(!is_js_identifier("Cärd"));
+        assert!(!is_js_identifier("my-story"));
+        assert!(!is_js_identifier(""));
+    }
+
+    #[test]
+    fn lists_the_default_variant_first() {
+        let template = StoryTemplate {
+            auto_play: Some(3000),
+            default_variant: Some("AutoPlay".to_string()),
+            ..StoryTemplate::new("Button")
+        };
+        assert!(template.render().contains("export const __namedExportsOrder = ['AutoPlay', 'Default'];"));
+        assert!(!StoryTemplate::new("Button").render().contains("__namedExportsOrder"));
+    }
+
+    #[test]
+    fn writes_csf2_stories_as_bound_templates() {
+        let js = StoryTemplate::new("Button").render();
+        assert!(js.contains("export const Default = Template.bind({});"));
+        assert!(!js.contains("{ render: Template }"));
+    }
+
+    #[test]
+    fn writes_csf3_stories_as_objects() {
+        let template = StoryTemplate { csf3: true, ..StoryTemplate::new("Button") };
+        let js = template.render();
+        assert!(js.contains("export const Default = { render: Template };"));
+        assert!(!js.contains("Template.bind({})"));
+    }
+}
@@ -0,0 +1,96 @@
+use notify::{Event, RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::mpsc;
+use std::time::Duration;
+
+// Directories whose contents never affect the generated stories
+const IGNORED_DIRS: &[&str] = &["target", "node_modules", "pkg", ".git", "storybook-static"];
+
+/// Settings for `watch_stories`
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Directories to watch for `.rs` changes
+    pub paths: Vec<PathBuf>,
+    /// Shell command that rebuilds the WASM package and stories
+    pub command: String,
+    /// How long to wait for more changes before rebuilding
+    pub debounce: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            paths: vec![PathBuf::from(".")],
+            command: "npm run build:wasm".to_string(),
+            debounce: Duration::from_millis(300),
+        }
+    }
+}
+
+/// Rebuild the stories whenever a `.rs` file under the watched paths changes
+///
+/// The rebuild regenerates the `.stories.js` files and the WASM package, which
+/// Storybook's Vite dev server picks up and hot-reloads by itself. Blocks until
+/// the watcher fails.
+pub fn watch_stories(options: &WatchOptions) -> notify::Result<()> {
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for path in &options.paths {
+        watcher.watch(path, RecursiveMode::Recursive)?;
+    }
+
+    eprintln!("storybook-watcher: watching {} for changes", display_paths(&options.paths));
+
+    while let Ok(event) = rx.recv() {
+        if !is_source_change(&event?) {
+            continue;
+        }
+
+        // Wait for the burst of events an editor save produces to settle
+        while let Ok(event) = rx.recv_timeout(options.debounce) {
+            event?;
+        }
+
+        rebuild(&options.command);
+    }
+
+    Ok(())
+}
+
+fn is_source_change(event: &Event) -> bool {
+    if !(event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove()) {
+        return false;
+    }
+
+    event.paths.iter().any(|path| {
+        path.extension().is_some_and(|ext| ext == "rs")
+            && !path.components().any(|component| {
+                IGNORED_DIRS.iter().any(|dir| component.as_os_str() == *dir)
+            })
+    })
+}
+
+fn rebuild(command: &str) {
+    eprintln!("storybook-watcher: running `{}`", command);
+
+    let status = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).status()
+    } else {
+        Command::new("sh").args(["-c", command]).status()
+    };
+
+    match status {
+        Ok(status) if status.success() => eprintln!("storybook-watcher: stories rebuilt"),
+        Ok(status) => eprintln!("storybook-watcher: `{}` failed with {}", command, status),
+        Err(err) => eprintln!("storybook-watcher: couldn't run `{}`: {}", command, err),
+    }
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use storybook_watcher::{watch_stories, WatchOptions};
+
+const USAGE: &str = "usage: storybook-watcher [--command <cmd>] [--debounce-ms <ms>] [paths...]";
+
+fn main() {
+    let mut options = WatchOptions::default();
+    let mut paths = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--command" => options.command = args.next().unwrap_or_else(|| exit_with_usage()),
+            "--debounce-ms" => {
+                let ms = args.next().and_then(|ms| ms.parse().ok()).unwrap_or_else(|| exit_with_usage());
+                options.debounce = Duration::from_millis(ms);
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    if !paths.is_empty() {
+        options.paths = paths;
+    }
+
+    if let Err(err) = watch_stories(&options) {
+        eprintln!("storybook-watcher: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,47 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use crate::StoryMeta;
+
+pub use storybook_derive::AriaMetadata;
+
+/// ARIA semantics documented for a story, for Storybook's a11y addon
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AriaRegistration {
+    pub role: Option<&'static str>,
+    pub aria_label: Option<&'static str>,
+    /// Name of the arg whose value is used as the accessible name
+    pub aria_label_from: Option<&'static str>,
+    pub aria_describedby: Option<&'static str>,
+}
+
+/// Trait for ARIA metadata, to be implemented by the derive macro
+pub trait AriaMetadata {
+    fn aria_metadata() -> AriaRegistration;
+}
+
+// Global registry for ARIA metadata, keyed by story name
+static ARIA_REGISTRY: Lazy<Mutex<HashMap<String, AriaRegistration>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Register a story's ARIA metadata with the global registry
+#[doc(hidden)]
+pub fn register_aria<T: StoryMeta + AriaMetadata>() {
+    ARIA_REGISTRY
+        .lock()
+        .unwrap()
+        .insert(T::name().to_string(), T::aria_metadata());
+}
+
+/// Get the ARIA metadata for a given story name
+#[wasm_bindgen]
+pub fn get_aria_metadata(story_name: &str) -> JsValue {
+    let registry = ARIA_REGISTRY.lock().unwrap();
+    match registry.get(story_name) {
+        Some(metadata) => serde_wasm_bindgen::to_value(metadata).unwrap_or(JsValue::NULL),
+        None => JsValue::NULL,
+    }
+}
@@ -0,0 +1,64 @@
+use serde::Deserialize;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+// One row of `twiggy top --format json`
+#[derive(Deserialize)]
+struct TwiggyItem {
+    name: String,
+    shallow_size: usize,
+}
+
+/// Estimate how many bytes each story adds to a built WASM bundle, largest first
+///
+/// Runs [`twiggy`](https://github.com/rustwasm/twiggy) over the bundle and adds up the
+/// functions and data whose names mention the story's struct, e.g.
+/// `<example::Button as storybook::Story>::to_story`. Code shared between stories isn't
+/// counted, and the bundle needs its name section, so analyze a build that wasn't stripped.
+///
+/// ```ignore
+/// // build.rs or a small CLI, with `cargo install twiggy`
+/// let sizes = storybook::bundle_analyzer::analyze_story_sizes("pkg/example_bg.wasm", &["Button", "Card"])?;
+/// for (story, bytes) in sizes {
+///     println!("{story}: {bytes} bytes");
+/// }
+/// ```
+pub fn analyze_story_sizes(wasm_path: impl AsRef<Path>, story_names: &[&str]) -> io::Result<Vec<(String, usize)>> {
+    let output = Command::new("twiggy")
+        .args(["top", "--format", "json"])
+        .arg(wasm_path.as_ref())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "twiggy failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let items: Vec<TwiggyItem> = serde_json::from_slice(&output.stdout).map_err(io::Error::other)?;
+    let mut sizes: Vec<(String, usize)> = story_names
+        .iter()
+        .map(|story| {
+            let size = items
+                .iter()
+                .filter(|item| mentions_type(&item.name, story))
+                .map(|item| item.shallow_size)
+                .sum();
+            (story.to_string(), size)
+        })
+        .collect();
+    sizes.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+
+    Ok(sizes)
+}
+
+// Whether a symbol name refers to the type as a whole path segment, so `Button` doesn't match `ButtonSize`
+fn mentions_type(symbol: &str, type_name: &str) -> bool {
+    symbol.match_indices(type_name).any(|(start, _)| {
+        let before = symbol[..start].chars().next_back();
+        let after = symbol[start + type_name.len()..].chars().next();
+        before.is_none_or(|c| matches!(c, ':' | '<' | ' ' | '&'))
+            && after.is_none_or(|c| !(c.is_alphanumeric() || c == '_'))
+    })
+}
@@ -0,0 +1,297 @@
+use std::path::Path;
+
+/// Why a story file couldn't be migrated
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The file couldn't be read
+    Io(std::io::Error),
+    /// No `export const X = Template.bind({})` story was found
+    NotCsf2,
+    /// Stories are bound to `Template`, but it isn't declared
+    MissingTemplate,
+    /// A string, comment or bracket opened on this line is never closed
+    Unterminated { line: usize },
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::Io(err) => write!(f, "couldn't read story file: {}", err),
+            MigrationError::NotCsf2 => write!(f, "no `Template.bind({{}})` stories found"),
+            MigrationError::MissingTemplate => write!(f, "stories are bound to `Template`, but it isn't declared"),
+            MigrationError::Unterminated { line } => write!(f, "unterminated string, comment or bracket on line {}", line),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+/// Rewrite a CSF2 `.stories.js` file, as written for Storybook 6, to CSF3 story objects
+///
+/// `const Template = (args) => ...` with `export const X = Template.bind({})` and `X.args = {...}`
+/// becomes `export const X = { render: (args) => ..., args: {...} }`, and any other `X.prop = ...`
+/// assignment, such as `play` or `parameters`, moves into the object too. A template used by a
+/// single story is inlined; otherwise the stories share it as `render: Template`. The rest of the
+/// file is kept as is. Only the subset of JS these files are written in is understood, so check
+/// the result of migrating hand-edited files.
+///
+/// ```ignore
+/// let path = Path::new("storybook/stories/Button.stories.js");
+/// std::fs::write(path, storybook::compat::csf2_to_csf3_migrator(path)?)?;
+/// ```
+pub fn csf2_to_csf3_migrator(file_path: &Path) -> Result<String, MigrationError> {
+    let source = std::fs::read_to_string(file_path).map_err(MigrationError::Io)?;
+    migrate_source(&source)
+}
+
+// What a top-level statement of a CSF2 file is
+enum Statement<'a> {
+    Template { value: &'a str },
+    Story { name: &'a str },
+    StoryProperty { name: &'a str, property: &'a str, value: &'a str },
+    Other,
+}
+
+fn migrate_source(source: &str) -> Result<String, MigrationError> {
+    let statements = split_statements(source)?;
+    let story_names: Vec<&str> = statements
+        .iter()
+        .filter_map(|(_, statement)| match classify(statement, &[]) {
+            Statement::Story { name } => Some(name),
+            _ => None,
+        })
+        .collect();
+    if story_names.is_empty() {
+        return Err(MigrationError::NotCsf2);
+    }
+
+    let classified: Vec<(&str, &str, Statement)> = statements
+        .iter()
+        .map(|(trivia, statement)| (*trivia, *statement, classify(statement, &story_names)))
+        .collect();
+    let template = classified
+        .iter()
+        .find_map(|(_, _, statement)| match statement {
+            Statement::Template { value } => Some(*value),
+            _ => None,
+        })
+        .ok_or(MigrationError::MissingTemplate)?;
+    // Inlining a template shared by several stories would copy it into each of them
+    let render = if story_names.len() == 1 { template } else { "Template" };
+
+    let mut migrated = String::new();
+    for (trivia, statement, kind) in &classified {
+        match kind {
+            Statement::Template { .. } if story_names.len() == 1 => skip_trivia(&mut migrated, trivia),
+            Statement::StoryProperty { .. } => skip_trivia(&mut migrated, trivia),
+            Statement::Story { name } => {
+                let mut properties = vec![("render", render)];
+                properties.extend(classified.iter().filter_map(|(_, _, kind)| match kind {
+                    Statement::StoryProperty { name: story, property, value } if story == name => Some((*property, *value)),
+                    _ => None,
+                }));
+
+                migrated.push_str(trivia);
+                migrated.push_str(&format!("export const {} = {{\n", name));
+                for (property, value) in properties {
+                    migrated.push_str(&format!("  {}: {},\n", property, indent_continuation_lines(value)));
+                }
+                migrated.push_str("};");
+            }
+            _ => {
+                migrated.push_str(trivia);
+                migrated.push_str(statement);
+            }
+        }
+    }
+    // Whatever follows the last statement, e.g. the final newline
+    let consumed: usize = statements.iter().map(|(trivia, statement)| trivia.len() + statement.len()).sum();
+    migrated.push_str(&source[consumed..]);
+
+    Ok(migrated)
+}
+
+// Dropped statements keep comments before them, but not the blank lines
+fn skip_trivia(migrated: &mut String, trivia: &str) {
+    if !trivia.trim().is_empty() {
+        migrated.push_str(trivia);
+    }
+}
+
+fn classify<'a>(statement: &'a str, story_names: &[&str]) -> Statement<'a> {
+    let body = statement.trim_end().trim_end_matches(';').trim_end();
+
+    let declaration = ["const ", "let ", "var "]
+        .iter()
+        .find_map(|keyword| body.strip_prefix(keyword))
+        .and_then(|rest| rest.split_once('='));
+    if let Some((name, value)) = declaration {
+        if name.trim() == "Template" {
+            return Statement::Template { value: value.trim() };
+        }
+    }
+
+    let export = body.strip_prefix("export const ").and_then(|rest| rest.split_once('='));
+    if let Some((name, value)) = export {
+        let value: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+        if value == "Template.bind({})" && is_identifier(name.trim()) {
+            return Statement::Story { name: name.trim() };
+        }
+    }
+
+    let assignment = body
+        .split_once('=')
+        .filter(|(_, value)| !value.starts_with(['=', '>']))
+        .and_then(|(target, value)| Some((target.trim().split_once('.')?, value)));
+    if let Some(((name, property), value)) = assignment {
+        if story_names.contains(&name) && is_identifier(property) {
+            return Statement::StoryProperty { name, property, value: value.trim() };
+        }
+    }
+
+    Statement::Other
+}
+
+fn is_identifier(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+// Values move one level into the story object, so their later lines are indented to match
+fn indent_continuation_lines(value: &str) -> String {
+    value
+        .lines()
+        .enumerate()
+        .map(|(i, line)| if i == 0 || line.is_empty() { line.to_string() } else { format!("  {}", line) })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Split the source into top-level statements, each with the whitespace and comments before it
+fn split_statements(source: &str) -> Result<Vec<(&str, &str)>, MigrationError> {
+    let bytes = source.as_bytes();
+    let mut statements = Vec::new();
+    let mut pos = 0;
+
+    loop {
+        let trivia_start = pos;
+        pos = skip_whitespace_and_comments(source, pos)?;
+        if pos >= bytes.len() {
+            break;
+        }
+        let statement_start = pos;
+        pos = statement_end(source, pos)?;
+        statements.push((&source[trivia_start..statement_start], &source[statement_start..pos]));
+    }
+
+    Ok(statements)
+}
+
+fn skip_whitespace_and_comments(source: &str, mut pos: usize) -> Result<usize, MigrationError> {
+    let bytes = source.as_bytes();
+    while pos < bytes.len() {
+        if bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        } else if source[pos..].starts_with("//") {
+            pos = source[pos..].find('\n').map_or(bytes.len(), |end| pos + end);
+        } else if source[pos..].starts_with("/*") {
+            let end = source[pos + 2..].find("*/").ok_or_else(|| unterminated(source, pos))?;
+            pos += end + 4;
+        } else {
+            break;
+        }
+    }
+    Ok(pos)
+}
+
+// Find where the statement starting at `start` ends: after a `;` outside any brackets, or at the
+// end of a line closing its last bracket, as JS inserts the semicolon there
+fn statement_end(source: &str, start: usize) -> Result<usize, MigrationError> {
+    let bytes = source.as_bytes();
+    let mut depth = 0usize;
+    let mut last_significant = 0u8;
+    let mut pos = start;
+
+    while pos < bytes.len() {
+        let byte = bytes[pos];
+        match byte {
+            b'\'' | b'"' | b'`' => pos = skip_string(source, pos)?,
+            b'/' if source[pos..].starts_with("//") || source[pos..].starts_with("/*") => {
+                pos = skip_whitespace_and_comments(source, pos)?;
+                continue;
+            }
+            b'(' | b'[' | b'{' => {
+                depth += 1;
+                pos += 1;
+            }
+            b')' | b']' | b'}' => {
+                depth = depth.checked_sub(1).ok_or_else(|| unterminated(source, pos))?;
+                pos += 1;
+            }
+            b';' if depth == 0 => return Ok(pos + 1),
+            b'\n' if depth == 0 && matches!(last_significant, b'}' | b')') && !continues(source, pos) => return Ok(pos),
+            _ => pos += 1,
+        }
+        if !byte.is_ascii_whitespace() {
+            last_significant = byte;
+        }
+    }
+
+    if depth > 0 {
+        return Err(unterminated(source, start));
+    }
+    Ok(bytes.len())
+}
+
+// Whether the next line carries on the statement, e.g. a chained `.then(...)`
+fn continues(source: &str, newline: usize) -> bool {
+    let rest = source[newline..].trim_start();
+    rest.starts_with(['.', ')', ']', '}', ',', '?', ':', '+', '-', '*', '&', '|']) || rest.starts_with("=>")
+}
+
+// Skip a string or template literal starting at `start`, returning the position after it
+fn skip_string(source: &str, start: usize) -> Result<usize, MigrationError> {
+    let bytes = source.as_bytes();
+    let quote = bytes[start];
+    let mut pos = start + 1;
+
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'\\' => pos += 2,
+            byte if byte == quote => return Ok(pos + 1),
+            b'\n' if quote != b'`' => break,
+            // Template literal placeholders hold code, which may contain strings and braces itself
+            b'$' if quote == b'`' && bytes.get(pos + 1) == Some(&b'{') => {
+                let mut depth = 0usize;
+                pos += 1;
+                loop {
+                    match bytes.get(pos) {
+                        None => return Err(unterminated(source, start)),
+                        Some(b'\'' | b'"' | b'`') => {
+                            pos = skip_string(source, pos)?;
+                            continue;
+                        }
+                        Some(b'{') => depth += 1,
+                        Some(b'}') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    pos += 1;
+                }
+                pos += 1;
+            }
+            _ => pos += 1,
+        }
+    }
+
+    Err(unterminated(source, start))
+}
+
+fn unterminated(source: &str, pos: usize) -> MigrationError {
+    MigrationError::Unterminated { line: source[..pos].matches('\n').count() + 1 }
+}
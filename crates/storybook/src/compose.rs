@@ -0,0 +1,83 @@
+use dominator::{Dom, html};
+
+pub use storybook_derive::StoryCompose;
+
+/// A story shown in a `#[derive(StoryCompose)]` story, e.g. `Button::Disabled`
+#[derive(Debug, Clone, Copy)]
+pub struct ComposeRegistration {
+    pub story: &'static str,
+    /// Variant whose default args are used, or the story's primary variant if `None`
+    pub variant: Option<&'static str>,
+}
+
+/// Render composed stories side by side in a row, each with its variant's default args
+///
+/// Used by `#[derive(StoryCompose)]`:
+///
+/// ```ignore
+/// #[derive(StoryCompose)]
+/// #[compose(stories = "Button::Default, Button::Disabled, Card")]
+/// struct ButtonStates;
+/// ```
+pub fn render_composed(stories: &'static [ComposeRegistration]) -> Dom {
+    html!("div", {
+        .style("display", "flex")
+        .style("flex-direction", "row")
+        .style("flex-wrap", "wrap")
+        .style("align-items", "flex-start")
+        .style("gap", "24px")
+        .children(stories.iter().map(|composed| composed_story(*composed)))
+    })
+}
+
+// One composed story with its name below it
+fn composed_story(composed: ComposeRegistration) -> Dom {
+    let label = match composed.variant {
+        Some(variant) => format!("{} / {}", composed.story, variant),
+        None => composed.story.to_string(),
+    };
+
+    html!("figure", {
+        .style("margin", "0")
+        .style("display", "flex")
+        .style("flex-direction", "column")
+        .style("gap", "8px")
+        .children(&mut [
+            html!("div", {
+                // Stories are mounted once the cell is in the document, like in Storybook
+                .after_inserted(move |container: web_sys::HtmlElement| {
+                    let mounted = variant_args(composed).and_then(|args| {
+                        crate::story_map::mount_with_default_args(composed.story, &args(), &container)
+                    });
+                    if let Err(err) = mounted {
+                        let message = err.as_string().unwrap_or_else(|| format!("{:?}", err));
+                        dominator::append_dom(&container, crate::render_error(&format!("Story '{}' failed to render", composed.story), &message));
+                    }
+                })
+            }),
+            html!("figcaption", {
+                .text(&label)
+                .style("font-family", "sans-serif")
+                .style("font-size", "12px")
+                .style("color", "#666")
+            }),
+        ])
+    })
+}
+
+// Look up the arg types of the composed variant, copied out so the registry isn't locked while the story renders
+fn variant_args(composed: ComposeRegistration) -> Result<crate::ArgsFn, wasm_bindgen::JsValue> {
+    let registry = crate::STORY_REGISTRY.lock().unwrap();
+    let meta = registry
+        .iter()
+        .find(|meta| meta.name == composed.story)
+        .ok_or_else(|| wasm_bindgen::JsValue::from_str(&format!("Story '{}' not found", composed.story)))?;
+
+    match composed.variant {
+        None => Ok(meta.args.clone()),
+        Some(variant) if variant == meta.primary_variant_name => Ok(meta.args.clone()),
+        Some(variant) => meta.variant_args.get(variant).map(|&args| std::sync::Arc::new(args) as crate::ArgsFn).ok_or_else(|| {
+            wasm_bindgen::JsValue::from_str(&format!("Story '{}' has no variant '{}'", composed.story, variant))
+        }),
+    }
+}
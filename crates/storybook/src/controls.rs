@@ -0,0 +1,207 @@
+use wasm_bindgen::prelude::*;
+use web_sys::js_sys;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::{ArgType, ControlType};
+
+/// Function rendering a custom control, given the arg's current value and a callback taking the new one
+pub type CustomControlFn = fn(JsValue, js_sys::Function) -> web_sys::Element;
+
+/// Function checking an arg's value, returning why it's invalid
+pub type ControlValidatorFn = fn(&JsValue, &ArgType) -> Result<(), String>;
+
+/// Control UI written in Rust, for args Storybook's built-in controls can't edit well
+///
+/// ```ignore
+/// struct BezierEditor;
+///
+/// impl InlineControlRenderer for BezierEditor {
+///     fn render_control(current_value: JsValue, on_change: js_sys::Function) -> web_sys::Element {
+///         // Call `on_change.call1(&JsValue::NULL, &new_value)` when the curve is edited
+///     }
+/// }
+///
+/// #[derive(Story)]
+/// struct Easing {
+///     #[story(control = "custom", renderer = "BezierEditor")]
+///     curve: [f64; 4],
+/// }
+/// ```
+pub trait InlineControlRenderer {
+    fn render_control(current_value: JsValue, on_change: js_sys::Function) -> web_sys::Element;
+}
+
+// Global registry for custom control renderers, keyed by story and field name
+static CUSTOM_CONTROLS_REGISTRY: Lazy<Mutex<HashMap<(String, String), CustomControlFn>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub(crate) fn register(story_name: &str, controls: HashMap<String, CustomControlFn>) {
+    let mut registry = CUSTOM_CONTROLS_REGISTRY.lock().unwrap();
+    for (field_name, render_fn) in controls {
+        registry.insert((story_name.to_string(), field_name), render_fn);
+    }
+}
+
+/// Render the custom control of one of a story's args, for mounting in the controls panel
+///
+/// `callback` is called with the arg's new value whenever the control changes it.
+#[wasm_bindgen]
+pub fn get_custom_control_element(
+    story_name: &str,
+    field_name: &str,
+    value: JsValue,
+    callback: js_sys::Function,
+) -> Result<web_sys::Element, JsValue> {
+    // Copied out so the registry isn't locked while user code runs
+    let render_fn = CUSTOM_CONTROLS_REGISTRY
+        .lock()
+        .unwrap()
+        .get(&(story_name.to_string(), field_name.to_string()))
+        .copied()
+        .ok_or_else(|| JsValue::from_str(&format!("Story '{}' has no custom control for '{}'", story_name, field_name)))?;
+
+    Ok(render_fn(value, callback))
+}
+
+/// Get the value a linked number arg takes when `field_name` is set to `value`
+///
+/// Works in both directions: for `width` with `linked_to = "height", aspect_ratio = "16/9"`,
+/// setting `width` gives the height and setting `height` gives the width.
+#[wasm_bindgen]
+pub fn get_linked_control_value(story_name: &str, field_name: &str, value: f64) -> Result<f64, JsValue> {
+    let args_fn = crate::story_args_fn(story_name)
+        .ok_or_else(|| JsValue::from_str(&format!("Story '{}' not found", story_name)))?;
+
+    let args = args_fn();
+    if let Some((_, ratio)) = args.iter().find(|arg| arg.name == field_name).and_then(|arg| arg.linked_field.as_ref()) {
+        return Ok(value / ratio);
+    }
+    args.iter()
+        .filter_map(|arg| arg.linked_field.as_ref())
+        .find(|(linked_field, _)| linked_field == field_name)
+        .map(|(_, ratio)| value * ratio)
+        .ok_or_else(|| JsValue::from_str(&format!("Story '{}' has no linked control for '{}'", story_name, field_name)))
+}
+
+/// Check of an arg's value, run before the story renders
+///
+/// The built-in `Required`, `MinLength` and `Bounds` validators check every arg against its arg
+/// type. Register your own per field with `register_story_validator`:
+///
+/// ```ignore
+/// struct NoTrailingSpace;
+///
+/// impl ControlValidator for NoTrailingSpace {
+///     fn validate(value: &JsValue, arg_type: &ArgType) -> Result<(), String> {
+///         match value.as_string() {
+///             Some(text) if text.ends_with(' ') => Err(format!("Field '{}' must not end with a space", arg_type.name)),
+///             _ => Ok(()),
+///         }
+///     }
+/// }
+///
+/// register_story_validator("Button", "label", NoTrailingSpace::validate);
+/// ```
+pub trait ControlValidator {
+    fn validate(value: &JsValue, arg_type: &ArgType) -> Result<(), String>;
+}
+
+/// Rejects `null` for args that aren't optional, which would otherwise fail to deserialize
+pub struct Required;
+
+impl ControlValidator for Required {
+    fn validate(value: &JsValue, arg_type: &ArgType) -> Result<(), String> {
+        if arg_type.required && value.is_null() {
+            return Err(format!("Field '{}' is required", arg_type.name));
+        }
+        Ok(())
+    }
+}
+
+/// Rejects text controls shorter than their `min_length`
+pub struct MinLength;
+
+impl ControlValidator for MinLength {
+    fn validate(value: &JsValue, arg_type: &ArgType) -> Result<(), String> {
+        match (&arg_type.control, value.as_string()) {
+            (ControlType::Text, Some(text)) => arg_type.check_length(&text),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Rejects number and range controls outside their `min`/`max`
+pub struct Bounds;
+
+impl ControlValidator for Bounds {
+    fn validate(value: &JsValue, arg_type: &ArgType) -> Result<(), String> {
+        match (&arg_type.control, value.as_f64()) {
+            (ControlType::Number | ControlType::Range, Some(number)) => arg_type.check_bounds(number),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Rejects select values that aren't one of the arg's options, checked for `external_options` selects
+pub struct OneOf;
+
+impl ControlValidator for OneOf {
+    fn validate(value: &JsValue, arg_type: &ArgType) -> Result<(), String> {
+        match (&arg_type.options, value.as_string()) {
+            (Some(options), Some(option)) if !options.contains(&option) => {
+                Err(format!("Field '{}' must be one of {}, got '{}'", arg_type.name, options.join(", "), option))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// Validators every arg is checked with
+const BUILT_IN_VALIDATORS: &[ControlValidatorFn] = &[Required::validate, MinLength::validate, Bounds::validate];
+
+// Custom validators of one field, run in registration order
+type FieldValidators = Vec<ControlValidatorFn>;
+
+// Global registry for custom validators, keyed by story and field name
+static VALIDATOR_REGISTRY: Lazy<Mutex<HashMap<(String, String), FieldValidators>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Check one of a story's args with `validator` before the story renders, after the built-in validators
+pub fn register_story_validator(story_name: &str, field_name: &str, validator: ControlValidatorFn) {
+    VALIDATOR_REGISTRY
+        .lock()
+        .unwrap()
+        .entry((story_name.to_string(), field_name.to_string()))
+        .or_default()
+        .push(validator);
+}
+
+/// Check a story's args with the built-in and registered validators
+///
+/// Fails with `{ story, errors: [{ field, message }] }` listing every invalid arg.
+pub(crate) fn validate_args(story_name: &str, arg_types: &[ArgType], args: &JsValue) -> Result<(), JsValue> {
+    if !args.is_object() {
+        return Ok(());
+    }
+
+    // Copied out so the registry isn't locked while user code runs
+    let custom_validators = VALIDATOR_REGISTRY.lock().unwrap().clone();
+    let mut errors = Vec::new();
+    for arg_type in arg_types {
+        let value = js_sys::Reflect::get(args, &JsValue::from_str(&arg_type.name)).unwrap_or(JsValue::UNDEFINED);
+        let custom = custom_validators.get(&(story_name.to_string(), arg_type.name.clone()));
+        for validator in BUILT_IN_VALIDATORS.iter().chain(custom.into_iter().flatten()) {
+            if let Err(message) = validator(&value, arg_type) {
+                errors.push(serde_json::json!({ "field": arg_type.name, "message": message }));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(serde_wasm_bindgen::to_value(&serde_json::json!({ "story": story_name, "errors": errors }))
+        .unwrap_or_else(|_| JsValue::from_str(&format!("Story '{}' has invalid args", story_name))))
+}
@@ -0,0 +1,74 @@
+use crate::{ENUM_DEFAULT_REGISTRY, ENUM_REGISTRY};
+
+/// Format the registered stories as a table, for tracking down registration problems
+///
+/// ```ignore
+/// register_all_stories();
+/// println!("{}", storybook::debug::dump_story_registry());
+/// ```
+pub fn dump_story_registry() -> String {
+    let stories = crate::collect_stories(|meta| Some((meta.name, meta.primary_variant_name, meta.args.clone())));
+
+    let rows: Vec<[String; 4]> = stories
+        .into_iter()
+        .map(|(name, variant, args)| {
+            let args = args();
+            let controls: Vec<String> = args
+                .iter()
+                .map(|arg| match serde_json::to_value(&arg.control) {
+                    Ok(serde_json::Value::String(control)) => format!("{}: {}", arg.name, control),
+                    _ => arg.name.clone(),
+                })
+                .collect();
+            [name.to_string(), variant.to_string(), args.len().to_string(), controls.join(", ")]
+        })
+        .collect();
+
+    format_table(["story", "variant", "args", "controls"], rows)
+}
+
+/// Format the registered enums and their select options as a table
+pub fn dump_enum_registry() -> String {
+    let options = ENUM_REGISTRY.lock().unwrap();
+    let defaults = ENUM_DEFAULT_REGISTRY.lock().unwrap();
+
+    let mut rows: Vec<[String; 3]> = options
+        .iter()
+        .map(|(type_name, options)| {
+            let default_option = defaults.get(type_name).copied().unwrap_or("-");
+            [type_name.clone(), default_option.to_string(), options.join(", ")]
+        })
+        .collect();
+    // The registry is a map, so sort for a stable listing
+    rows.sort();
+
+    format_table(["enum", "default", "options"], rows)
+}
+
+// Lay out rows under their headers with `|`-separated, padded columns
+fn format_table<const N: usize>(headers: [&str; N], rows: Vec<[String; N]>) -> String {
+    let mut widths = headers.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![
+        format_row(headers.to_vec()),
+        widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("-|-"),
+    ];
+    lines.extend(rows.iter().map(|row| format_row(row.iter().map(String::as_str).collect())));
+    lines.join("\n")
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+use crate::{ArgType, ControlType};
+
+/// A problem found in a registered story's configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoryDiagnostic {
+    pub story_name: &'static str,
+    pub field_name: String,
+    pub issue: DiagnosticKind,
+}
+
+/// The kinds of problems `validate_stories` looks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// A required arg whose control has no sensible empty value has no default
+    MissingDefault,
+    /// The arg's default value can't be shown by its control, e.g. `default = "'big'"` on a number
+    InvalidControlType,
+    /// A select control has no options to choose from
+    MissingStorySelectImpl,
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::MissingDefault => write!(f, "required arg has no default value"),
+            DiagnosticKind::InvalidControlType => write!(f, "default value doesn't match the control type"),
+            DiagnosticKind::MissingStorySelectImpl => write!(f, "select control has no options (is StorySelect derived?)"),
+        }
+    }
+}
+
+impl fmt::Display for StoryDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}: {}", self.story_name, self.field_name, self.issue)
+    }
+}
+
+/// Check every registered story for configuration mistakes
+///
+/// Call this after `register_all_stories()`, e.g. from a test or a build step,
+/// to catch broken stories before they reach Storybook.
+pub fn validate_stories() -> Vec<StoryDiagnostic> {
+    let stories = crate::collect_stories(|meta| Some((meta.name, meta.args.clone())));
+
+    stories
+        .into_iter()
+        .flat_map(|(story_name, args)| {
+            args().into_iter().filter_map(move |arg| {
+                validate_arg(&arg).map(|issue| StoryDiagnostic {
+                    story_name,
+                    field_name: arg.name.clone(),
+                    issue,
+                })
+            })
+        })
+        .collect()
+}
+
+fn validate_arg(arg: &ArgType) -> Option<DiagnosticKind> {
+    // Dependent selects only get their options once the args they depend on are known
+    if matches!(arg.control, ControlType::Select) && !arg.depends_on.is_empty() {
+        return None;
+    }
+
+    match (&arg.control, &arg.default_value) {
+        (ControlType::Select | ControlType::InlineRadio, _) if arg.options.as_ref().is_none_or(|options| options.is_empty()) => {
+            Some(DiagnosticKind::MissingStorySelectImpl)
+        }
+        (ControlType::Color | ControlType::Object | ControlType::Range, None) if arg.required => {
+            Some(DiagnosticKind::MissingDefault)
+        }
+        (ControlType::Number | ControlType::Range, Some(default)) if default.trim().parse::<f64>().is_err() => {
+            Some(DiagnosticKind::InvalidControlType)
+        }
+        (ControlType::Boolean | ControlType::Switch, Some(default))
+            if !matches!(default.trim(), "true" | "false") =>
+        {
+            Some(DiagnosticKind::InvalidControlType)
+        }
+        (ControlType::Select | ControlType::InlineRadio, Some(default)) => {
+            let default = default.trim().trim_matches(|c| c == '\'' || c == '"');
+            let options = arg.options.as_deref().unwrap_or_default();
+            if options.iter().any(|option| option == default) {
+                None
+            } else {
+                Some(DiagnosticKind::InvalidControlType)
+            }
+        }
+        _ => None,
+    }
+}
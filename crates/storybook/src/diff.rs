@@ -0,0 +1,85 @@
+use wasm_bindgen::prelude::*;
+use web_sys::js_sys;
+
+use crate::ArgType;
+
+/// An arg whose value differs between two renders of a story
+#[derive(Debug, Clone)]
+pub struct ArgChange {
+    pub field: String,
+    pub old: JsValue,
+    pub new: JsValue,
+}
+
+/// The args that changed between two renders of a story, in the order of the story's fields
+#[derive(Debug, Clone, Default)]
+pub struct StoryArgsDiff {
+    pub changes: Vec<ArgChange>,
+}
+
+impl StoryArgsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The diff as `{ changes: [{ field, old, new }] }`, for addon panels
+    pub fn to_js(&self) -> JsValue {
+        let changes = js_sys::Array::new();
+        for change in &self.changes {
+            let entry = js_sys::Object::new();
+            // Setting properties on a fresh object can't fail
+            let _ = js_sys::Reflect::set(&entry, &"field".into(), &JsValue::from_str(&change.field));
+            let _ = js_sys::Reflect::set(&entry, &"old".into(), &change.old);
+            let _ = js_sys::Reflect::set(&entry, &"new".into(), &change.new);
+            changes.push(&entry);
+        }
+
+        let diff = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&diff, &"changes".into(), &changes);
+        diff.into()
+    }
+}
+
+/// Find the args in `schema` whose values differ between `old` and `new`
+///
+/// Values are compared by their JSON, so objects with the same contents are equal. Args
+/// missing from either side count as `undefined`, and keys that aren't in `schema` are ignored.
+pub fn compute_args_diff(old: JsValue, new: JsValue, schema: &[ArgType]) -> StoryArgsDiff {
+    let changes = schema
+        .iter()
+        .filter_map(|arg| {
+            let old = arg_value(&old, &arg.name);
+            let new = arg_value(&new, &arg.name);
+            (!args_equal(&old, &new)).then(|| ArgChange { field: arg.name.clone(), old, new })
+        })
+        .collect();
+
+    StoryArgsDiff { changes }
+}
+
+/// Get the args that changed between two renders of a story, as `{ changes: [{ field, old, new }] }`
+#[wasm_bindgen]
+pub fn get_args_diff(story_name: &str, old_args: JsValue, new_args: JsValue) -> Result<JsValue, JsValue> {
+    let args_fn = crate::story_args_fn(story_name)
+        .ok_or_else(|| JsValue::from_str(&format!("Story '{}' not found", story_name)))?;
+
+    Ok(compute_args_diff(old_args, new_args, &args_fn()).to_js())
+}
+
+// The arg's value, or `undefined` if the args aren't an object or don't have it
+fn arg_value(args: &JsValue, name: &str) -> JsValue {
+    if !args.is_object() {
+        return JsValue::UNDEFINED;
+    }
+    js_sys::Reflect::get(args, &JsValue::from_str(name)).unwrap_or(JsValue::UNDEFINED)
+}
+
+fn args_equal(old: &JsValue, new: &JsValue) -> bool {
+    if js_sys::Object::is(old, new) {
+        return true;
+    }
+    // Values that aren't JSON, e.g. functions, are only equal if they're the same value
+    let old = serde_wasm_bindgen::from_value::<serde_json::Value>(old.clone());
+    let new = serde_wasm_bindgen::from_value::<serde_json::Value>(new.clone());
+    matches!((old, new), (Ok(old), Ok(new)) if old == new)
+}
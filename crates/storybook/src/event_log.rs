@@ -0,0 +1,92 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    // Events pushed by each story since it was last rendered
+    static EVENT_LOG: RefCell<HashMap<String, Vec<(String, JsValue)>>> = RefCell::new(HashMap::new());
+
+    // Story that events are recorded against; event handlers run after rendering has finished
+    static ACTIVE_STORY: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Record of the events components emit, for asserting on in play functions and tests
+///
+/// ```ignore
+/// .event(move |_: dominator::events::Click| {
+///     EventLog::push("click", JsValue::NULL);
+/// })
+/// ```
+pub struct EventLog;
+
+impl EventLog {
+    /// Record an event against the story that was rendered last
+    ///
+    /// Does nothing when no story has been rendered, e.g. outside Storybook.
+    pub fn push(event_name: &str, data: JsValue) {
+        let Some(story) = ACTIVE_STORY.with(|active| active.borrow().clone()) else {
+            return;
+        };
+        EVENT_LOG.with(|log| {
+            log.borrow_mut()
+                .entry(story)
+                .or_default()
+                .push((event_name.to_string(), data));
+        });
+    }
+
+    /// Names of the events recorded for a story, in the order they fired
+    pub fn events_of(story_name: &str) -> Vec<String> {
+        EVENT_LOG.with(|log| {
+            log.borrow()
+                .get(story_name)
+                .map(|events| events.iter().map(|(name, _)| name.clone()).collect())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Forget every recorded event
+    pub fn clear() {
+        EVENT_LOG.with(|log| log.borrow_mut().clear());
+    }
+}
+
+// Called when a story starts rendering, so its log only holds events from the current render
+pub(crate) fn start_story(name: &str) {
+    ACTIVE_STORY.with(|active| *active.borrow_mut() = Some(name.to_string()));
+    EVENT_LOG.with(|log| log.borrow_mut().remove(name));
+}
+
+#[derive(Serialize)]
+struct LoggedEvent {
+    event: String,
+    #[serde(with = "serde_wasm_bindgen::preserve")]
+    data: JsValue,
+}
+
+/// Get the events recorded for a story as an array of `{ event, data }` objects
+#[wasm_bindgen]
+pub fn get_event_log(story_name: &str) -> JsValue {
+    let events: Vec<LoggedEvent> = EVENT_LOG.with(|log| {
+        log.borrow()
+            .get(story_name)
+            .map(|events| {
+                events
+                    .iter()
+                    .map(|(event, data)| LoggedEvent {
+                        event: event.clone(),
+                        data: data.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+    serde_wasm_bindgen::to_value(&events).unwrap_or(JsValue::NULL)
+}
+
+/// Clear the event log of every story
+#[wasm_bindgen]
+pub fn clear_event_log() {
+    EventLog::clear();
+}
@@ -0,0 +1,222 @@
+use dominator::{Dom, html};
+use futures_signals::signal::{Mutable, SignalExt};
+use serde::Deserialize;
+
+use crate::{ArgType, Story, StoryMeta};
+
+/// Component library index page, showing every registered story as a card
+///
+/// Each card has the story's name, a thumbnail of it rendered with its default args and
+/// scaled down to fit, and a link to the story in Storybook. Large libraries are split
+/// into pages. Render it from a story of your own to pick the layout:
+///
+/// ```ignore
+/// #[derive(Story, Deserialize)]
+/// struct ComponentLibrary {}
+///
+/// impl Story for ComponentLibrary {
+///     fn to_story(self) -> Dom {
+///         StoryGrid::new(3, (240, 160)).with_page_size(12).to_story()
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StoryGrid {
+    columns: usize,
+    thumbnail_size: (u32, u32),
+    page_size: usize,
+}
+
+impl Default for StoryGrid {
+    fn default() -> Self {
+        Self::new(4, (240, 160))
+    }
+}
+
+impl StoryGrid {
+    /// Create a grid with `columns` cards per row and thumbnails of `(width, height)` pixels
+    pub fn new(columns: usize, thumbnail_size: (u32, u32)) -> Self {
+        Self {
+            columns: columns.max(1),
+            thumbnail_size,
+            page_size: 24,
+        }
+    }
+
+    /// Show this many stories per page, 24 by default
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+}
+
+impl StoryMeta for StoryGrid {
+    type StoryArgs = StoryGrid;
+
+    fn name() -> &'static str {
+        "StoryGrid"
+    }
+
+    fn args() -> Vec<ArgType> {
+        Vec::new()
+    }
+}
+
+impl Story for StoryGrid {
+    fn to_story(self) -> Dom {
+        if crate::story_map::in_thumbnail() {
+            return crate::story_map::overview_placeholder("Component library");
+        }
+        // Clamped again here, as args deserialized from Storybook skip `new` and `with_page_size`
+        let grid = Self {
+            columns: self.columns.max(1),
+            page_size: self.page_size.max(1),
+            ..self
+        };
+
+        let stories: Vec<GridStory> = crate::collect_stories(|meta| {
+            (meta.name != Self::name()).then(|| GridStory {
+                name: meta.name,
+                args: meta.args.clone(),
+                // Cards render in the preview iframe, so the link is relative to it
+                url: format!("./?path=/story/{}", crate::integration::story_id(meta)),
+            })
+        });
+
+        let page_count = stories.len().div_ceil(grid.page_size).max(1);
+        let page = Mutable::new(0usize);
+
+        html!("div", {
+            .style("font-family", "sans-serif")
+            .style("display", "flex")
+            .style("flex-direction", "column")
+            .style("gap", "16px")
+            .child_signal(page.signal().map(move |page| Some(grid.page(&stories, page))))
+            .apply(|dom| if page_count > 1 { dom.child(pagination(page.clone(), page_count)) } else { dom })
+        })
+    }
+}
+
+// A registered story, as shown on a card
+#[derive(Clone)]
+struct GridStory {
+    name: &'static str,
+    args: crate::ArgsFn,
+    url: String,
+}
+
+impl StoryGrid {
+    // The cards of one page of stories
+    fn page(&self, stories: &[GridStory], page: usize) -> Dom {
+        let (width, height) = self.thumbnail_size;
+        html!("div", {
+            .style("display", "grid")
+            .style("grid-template-columns", format!("repeat({}, {}px)", self.columns, width))
+            .style("gap", "24px")
+            .children(
+                stories
+                    .iter()
+                    .skip(page * self.page_size)
+                    .take(self.page_size)
+                    .map(|story| card(story.clone(), width, height))
+            )
+        })
+    }
+}
+
+// A story's thumbnail, with its name and a link to it below
+fn card(story: GridStory, width: u32, height: u32) -> Dom {
+    html!("figure", {
+        .style("margin", "0")
+        .style("display", "flex")
+        .style("flex-direction", "column")
+        .style("gap", "8px")
+        .children(&mut [
+            html!("div", {
+                .style("width", format!("{}px", width))
+                .style("height", format!("{}px", height))
+                .style("overflow", "hidden")
+                .style("border", "1px solid #ddd")
+                .style("border-radius", "4px")
+                .child(html!("div", {
+                    .style("display", "inline-block")
+                    .style("transform-origin", "top left")
+                    // Stories are mounted once the thumbnail is in the document, like in Storybook
+                    .after_inserted(move |container: web_sys::HtmlElement| {
+                        match crate::story_map::mount_with_default_args(story.name, &(story.args)(), &container) {
+                            Ok(()) => scale_to_fit(&container, width, height),
+                            Err(err) => {
+                                let message = err.as_string().unwrap_or_else(|| format!("{:?}", err));
+                                dominator::append_dom(&container, crate::render_error(&format!("Story '{}' failed to render", story.name), &message));
+                            }
+                        }
+                    })
+                }))
+            }),
+            html!("figcaption", {
+                .style("display", "flex")
+                .style("justify-content", "space-between")
+                .style("font-size", "12px")
+                .children(&mut [
+                    html!("span", {
+                        .style("color", "#666")
+                        .text(story.name)
+                    }),
+                    html!("a", {
+                        // Cards render in the preview iframe, so the link opens in the manager around it
+                        .attr("href", &story.url)
+                        .attr("target", "_top")
+                        .style("color", "#1ea7fd")
+                        .text("View in Storybook")
+                    }),
+                ])
+            }),
+        ])
+    })
+}
+
+// Shrink the rendered story so all of it fits the thumbnail, without enlarging small ones
+fn scale_to_fit(container: &web_sys::HtmlElement, width: u32, height: u32) {
+    let (story_width, story_height) = (container.scroll_width(), container.scroll_height());
+    if story_width <= 0 || story_height <= 0 {
+        return;
+    }
+    let scale = (width as f64 / story_width as f64).min(height as f64 / story_height as f64).min(1.0);
+    let _ = container.style().set_property("transform", &format!("scale({})", scale));
+}
+
+// Previous/next buttons with the current page between them
+fn pagination(page: Mutable<usize>, page_count: usize) -> Dom {
+    html!("div", {
+        .style("display", "flex")
+        .style("align-items", "center")
+        .style("gap", "12px")
+        .children(&mut [
+            html!("button", {
+                .text("Previous")
+                .attr_signal("disabled", page.signal().map(|page| (page == 0).then_some("")))
+                .event({
+                    let page = page.clone();
+                    move |_: dominator::events::Click| {
+                        page.replace_with(|page| page.saturating_sub(1));
+                    }
+                })
+            }),
+            html!("span", {
+                .style("font-size", "12px")
+                .text_signal(page.signal().map(move |page| format!("Page {} of {}", page + 1, page_count)))
+            }),
+            html!("button", {
+                .text("Next")
+                .attr_signal("disabled", page.signal().map(move |page| (page + 1 >= page_count).then_some("")))
+                .event({
+                    let page = page.clone();
+                    move |_: dominator::events::Click| {
+                        page.replace_with(|page| (*page + 1).min(page_count - 1));
+                    }
+                })
+            }),
+        ])
+    })
+}
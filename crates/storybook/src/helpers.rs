@@ -0,0 +1,94 @@
+use wasm_bindgen::prelude::*;
+use dominator::{Dom, html};
+
+use crate::{Story, StoryMeta};
+
+/// Builder for showing a component side by side at several viewport widths
+///
+/// A `Dom` can only be mounted once, so the component is given as a function that
+/// renders a fresh copy for each viewport.
+///
+/// ```ignore
+/// ResponsivePreview::new(|| button.to_story())
+///     .add_viewport("mobile", 375)
+///     .add_viewport("desktop", 1200)
+///     .build()
+/// ```
+pub struct ResponsivePreview {
+    render: Box<dyn Fn() -> Dom>,
+    viewports: Vec<(String, u32)>,
+}
+
+impl ResponsivePreview {
+    /// Start a preview of the component rendered by `render`
+    pub fn new(render: impl Fn() -> Dom + 'static) -> Self {
+        Self {
+            render: Box::new(render),
+            viewports: Vec::new(),
+        }
+    }
+
+    /// Add a viewport with the given label and width in pixels
+    pub fn add_viewport(mut self, label: &str, width_px: u32) -> Self {
+        self.viewports.push((label.to_string(), width_px));
+        self
+    }
+
+    /// Build the side-by-side layout, or the bare component if no viewports were added
+    pub fn build(self) -> Dom {
+        if self.viewports.is_empty() {
+            return (self.render)();
+        }
+
+        let frames: Vec<Dom> = self
+            .viewports
+            .iter()
+            .map(|(label, width_px)| {
+                html!("div", {
+                    .style("display", "flex")
+                    .style("flex-direction", "column")
+                    .style("gap", "8px")
+                    .children(&mut [
+                        html!("div", {
+                            .text(&format!("{} · {}px", label, width_px))
+                            .style("font-family", "sans-serif")
+                            .style("font-size", "12px")
+                            .style("color", "#666")
+                        }),
+                        html!("div", {
+                            .style("width", &format!("{}px", width_px))
+                            .style("overflow", "hidden")
+                            .style("border", "1px dashed #ccc")
+                            .child((self.render)())
+                        }),
+                    ])
+                })
+            })
+            .collect();
+
+        html!("div", {
+            .style("display", "flex")
+            .style("gap", "24px")
+            .style("align-items", "flex-start")
+            .style("overflow-x", "auto")
+            .children(frames)
+        })
+    }
+}
+
+/// Render a story inside the default mobile/desktop `ResponsivePreview`
+///
+/// Used for stories with `#[story(responsive_preview)]`.
+pub fn render_responsive<T: Story + StoryMeta>(args: JsValue, container: &web_sys::Element) {
+    let preview = ResponsivePreview::new(move || {
+        let component: T::StoryArgs = serde_wasm_bindgen::from_value(args.clone())
+            .unwrap_or_else(|err| panic!("Invalid args for story '{}': {}", T::name(), err));
+        let story: T = component.into();
+        story.to_story()
+    })
+    .add_viewport("mobile", 375)
+    .add_viewport("desktop", 1200)
+    .build();
+
+    dominator::append_dom(container, preview);
+}
@@ -0,0 +1,34 @@
+pub mod chromatic;
+pub mod percy;
+
+use crate::StoryRegistration;
+
+// The ID Storybook derives from the story's title (or its `component_id`) and its primary variant's export name
+pub(crate) fn story_id(meta: &StoryRegistration) -> String {
+    let component_id = meta.component_id.map_or_else(|| sanitize_id(&meta.title), sanitize_id);
+    format!("{}--{}", component_id, sanitize_id(&start_case(meta.primary_variant_name)))
+}
+
+// Storybook's ID sanitizing: lowercase, with runs of other characters replaced by one `-`
+fn sanitize_id(value: &str) -> String {
+    value
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// Split an export name into words, e.g. `PrimaryButton` to `Primary Button`, like Storybook's story names
+fn start_case(export_name: &str) -> String {
+    let mut words = String::with_capacity(export_name.len() + 4);
+    let mut previous: Option<char> = None;
+    for c in export_name.chars() {
+        if c.is_ascii_uppercase() && previous.is_some_and(|previous| previous.is_ascii_lowercase() || previous.is_ascii_digit()) {
+            words.push(' ');
+        }
+        words.push(c);
+        previous = Some(c);
+    }
+    words
+}
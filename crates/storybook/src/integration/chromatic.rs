@@ -0,0 +1,63 @@
+use serde_json::json;
+use std::path::Path;
+
+/// Chromatic config limited to the registered stories, for `chromatic --config-file`
+///
+/// Chromatic builds and captures the Storybook itself, so the config only names the
+/// project and lists the registered stories' titles in `onlyStoryNames`. Stories must be
+/// registered first.
+///
+/// ```ignore
+/// register_all_stories();
+/// ChromaticConfig::new()
+///     .project_id("Project:64cbcde96f99841e8b007d75")
+///     .write_config("chromatic.config.json")?;
+/// // npx chromatic --config-file chromatic.config.json
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ChromaticConfig {
+    project_id: Option<String>,
+    build_script_name: Option<String>,
+}
+
+impl ChromaticConfig {
+    /// Start a config without a project ID, which is then read from `CHROMATIC_PROJECT_TOKEN`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the Chromatic project the builds are published to
+    pub fn project_id(mut self, project_id: impl Into<String>) -> Self {
+        self.project_id = Some(project_id.into());
+        self
+    }
+
+    /// Set the npm script building the Storybook, `build-storybook` by default
+    pub fn build_script_name(mut self, build_script_name: impl Into<String>) -> Self {
+        self.build_script_name = Some(build_script_name.into());
+        self
+    }
+
+    /// The config as JSON
+    pub fn to_json(&self) -> String {
+        let story_names = crate::collect_stories(|meta| Some(format!("{}/*", meta.title)));
+
+        let mut config = serde_json::Map::new();
+        config.insert("$schema".to_string(), json!("https://www.chromatic.com/config-file.schema.json"));
+        if let Some(project_id) = &self.project_id {
+            config.insert("projectId".to_string(), json!(project_id));
+        }
+        if let Some(build_script_name) = &self.build_script_name {
+            config.insert("buildScriptName".to_string(), json!(build_script_name));
+        }
+        config.insert("onlyStoryNames".to_string(), json!(story_names));
+
+        // A map of JSON values always serializes
+        serde_json::to_string_pretty(&config).unwrap()
+    }
+
+    /// Write the config to `path`, e.g. `chromatic.config.json`
+    pub fn write_config(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+}
@@ -0,0 +1,85 @@
+use std::path::Path;
+
+/// Percy snapshot list of every registered story, for `percy snapshot`
+///
+/// Each story's primary variant is listed under its Storybook title, with the URL of the
+/// story rendered on its own (`iframe.html`), so Percy captures the component without the
+/// manager around it. Stories must be registered first.
+///
+/// Write it to its own file rather than `.percy.yml`, which Percy loads as its `version: 2`
+/// configuration instead.
+///
+/// ```ignore
+/// register_all_stories();
+/// PercyConfig::new()
+///     .storybook_url("http://localhost:6006")
+///     .widths(&[375, 1280])
+///     .write_snapshots("percy-snapshots.yml")?;
+/// // npx percy snapshot percy-snapshots.yml
+/// ```
+#[derive(Debug, Clone)]
+pub struct PercyConfig {
+    storybook_url: String,
+    widths: Vec<u32>,
+}
+
+impl Default for PercyConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PercyConfig {
+    /// Snapshot the stories of a Storybook running at `http://localhost:6006`, at Percy's default widths
+    pub fn new() -> Self {
+        Self {
+            storybook_url: "http://localhost:6006".to_string(),
+            widths: Vec::new(),
+        }
+    }
+
+    /// Set the URL Storybook is served from, e.g. a deployed Storybook
+    pub fn storybook_url(mut self, storybook_url: impl Into<String>) -> Self {
+        self.storybook_url = storybook_url.into();
+        self
+    }
+
+    /// Set the browser widths each story is captured at, in pixels
+    pub fn widths(mut self, widths: &[u32]) -> Self {
+        self.widths = widths.to_vec();
+        self
+    }
+
+    /// The snapshot list as YAML
+    pub fn to_yaml(&self) -> String {
+        let base_url = self.storybook_url.trim_end_matches('/');
+        let mut yaml = String::from("# Generated by storybook::integration::percy::PercyConfig - do not edit\n");
+        yaml.push_str("#\n# Usage: npx percy snapshot percy-snapshots.yml\n");
+
+        let stories = crate::collect_stories(|meta| Some((meta.title.clone(), super::story_id(meta))));
+        if stories.is_empty() {
+            yaml.push_str("[]\n");
+            return yaml;
+        }
+        for (title, story_id) in stories {
+            yaml.push_str(&format!("- name: {}\n", yaml_string(&title)));
+            let url = format!("{}/iframe.html?id={}&viewMode=story", base_url, story_id);
+            yaml.push_str(&format!("  url: {}\n", yaml_string(&url)));
+            if !self.widths.is_empty() {
+                let widths: Vec<String> = self.widths.iter().map(u32::to_string).collect();
+                yaml.push_str(&format!("  widths: [{}]\n", widths.join(", ")));
+            }
+        }
+        yaml
+    }
+
+    /// Write the snapshot list to `path`, e.g. `percy-snapshots.yml`
+    pub fn write_snapshots(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_yaml())
+    }
+}
+
+// Single-quoted YAML scalar, where quotes are escaped by doubling them
+fn yaml_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
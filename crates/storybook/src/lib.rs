@@ -1,11 +1,40 @@
 use wasm_bindgen::prelude::*;
 use dominator::{Dom, html};
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use once_cell::sync::Lazy;
 
+pub mod accessibility;
+pub mod bundle_analyzer;
+pub mod compat;
+pub mod compose;
+pub mod controls;
+pub mod debug;
+pub mod diagnostics;
+pub mod diff;
+pub mod event_log;
+pub mod grid;
+pub mod helpers;
+pub mod integration;
+pub mod meta;
+pub mod performance;
+pub mod preview;
+pub mod reactive;
+pub mod screenshots;
+pub mod story_map;
+pub mod testing;
+pub mod token_extraction;
+#[cfg(feature = "wasm_test")]
+pub mod wasm_test;
+
 // Re-export for use in derive macro
-pub use storybook_derive::{register_stories, Story as StoryDerive, StorySelect, register_enums, set_dominator_path};
+pub use storybook_derive::{register_stories, Story as StoryDerive, StorySelect, register_enums, register_aria, set_dominator_path, storybook_config};
+#[doc(hidden)]
+pub use storybook_derive::__write_story_file;
+#[doc(hidden)]
+pub use once_cell::sync::Lazy as __Lazy;
+/// The `.stories.js` template, shared by `#[derive(Story)]` and `storybook-codegen`
+pub use storybook_template as template;
 
 /// Control type for Storybook args
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,9 +44,37 @@ pub enum ControlType {
     Select,
     Color,
     Boolean,
+    Switch,
     Number,
+    Range,
+    Object,
+    /// Text control with a small DOM preview rendered next to it
+    Inline,
+    /// Control rendered by an `InlineControlRenderer`
+    Custom,
+    /// Row of radio buttons, optionally labelled with icons
+    #[serde(rename = "inline-radio")]
+    InlineRadio,
 }
 
+/// Function rendering the inline preview of an arg value in the controls panel
+pub type InlinePreviewFn = fn(JsValue) -> web_sys::Element;
+
+/// Function computing a select's options from the story's current args, e.g. states for a country
+pub type DependentOptionsFn = fn(JsValue) -> Vec<String>;
+
+/// Function returning a select's options, called once when the story is registered
+pub type OptionsFn = fn() -> Vec<String>;
+
+/// Function returning a story's arg types
+pub type ArgsFn = Arc<dyn Fn() -> Vec<ArgType> + Send + Sync>;
+
+/// Function rendering a story with the given args and mounting it into the container
+pub type RenderToDomFn = Arc<dyn Fn(JsValue, &web_sys::Element) + Send + Sync>;
+
+/// Function pushing new args into a rendered story's `Mutable`s, given the handles from `StoryMeta::live_handles`
+pub type LiveUpdateFn = fn(&dyn std::any::Any, JsValue) -> Result<(), JsValue>;
+
 /// Argument type information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArgType {
@@ -26,8 +83,346 @@ pub struct ArgType {
     pub control: ControlType,
     pub required: bool,
     pub options: Option<Vec<String>>,
+    /// Description shown in the docs table instead of the arg's name
+    pub description: Option<String>,
+    /// Lowest accepted value for number and range controls
+    pub min: Option<f64>,
+    /// Highest accepted value for number and range controls
+    pub max: Option<f64>,
+    /// Translation key the arg's value comes from
+    pub i18n_key: Option<String>,
+    /// Help shown in a tooltip on the arg's type in the docs table, e.g. the values it accepts
+    pub tooltip: Option<String>,
+    /// Select options grouped under a heading, as `(group name, options)`
+    pub option_groups: Option<Vec<(String, Vec<String>)>>,
+    /// Visible rows of the text control's textarea
+    pub textarea_rows: Option<usize>,
+    /// CSS `resize` mode of the text control's textarea
+    pub textarea_resize: Option<&'static str>,
+    /// Fewest characters accepted by a text control; `null` still passes for optional args
+    pub min_length: Option<usize>,
+    /// Number arg kept in proportion to this one, as `(field, this value / that value)`
+    pub linked_field: Option<(String, f64)>,
+    /// `Intl.NumberFormat` style used to display a number control's value, e.g. `currency`
+    pub number_format: Option<String>,
+    /// ISO 4217 currency code for the `currency` number format, e.g. `USD`
+    pub number_currency: Option<String>,
+    /// How a number control shows its value: `percentage` shows `0.0`-`1.0` as `0`-`100`
+    pub display_format: Option<String>,
+    /// Name of the control component registered in preview.js that edits a `Custom` arg
+    pub custom_control: Option<String>,
+    /// Labels shown for an inline radio's options instead of their names, in option order
+    pub radio_icons: Option<Vec<String>>,
+    /// Whether a select also accepts values typed in that aren't one of its options
+    pub allow_custom_value: bool,
+    /// Args whose values this arg's select options depend on
+    pub depends_on: Vec<String>,
+}
+
+impl ArgType {
+    /// The default value as JSON, reading JS literals such as `'#007bff'` and plain text such as enum variants
+    pub fn default_json(&self) -> Option<serde_json::Value> {
+        self.default_value.as_deref().map(story_map::default_arg_value)
+    }
+
+    /// Whether the control shows the value as a percentage, i.e. multiplied by 100
+    pub fn is_percentage(&self) -> bool {
+        self.display_format.as_deref() == Some("percentage")
+    }
+
+    /// A value as the control shows it, e.g. `50` for `0.5` with `display_format = "percentage"`
+    pub fn to_display_value(&self, value: serde_json::Value) -> serde_json::Value {
+        match value.as_f64() {
+            // Rounded so e.g. 0.07 shows as 7 rather than 7.000000000000001
+            Some(number) if self.is_percentage() => serde_json::json!((number * 100.0 * 1e6).round() / 1e6),
+            _ => value,
+        }
+    }
+
+    /// Text of the tooltip on the arg's type in the docs table (`table.type.detail`): the tooltip, then the translation key
+    pub fn type_detail(&self) -> Option<String> {
+        let i18n_key = self.i18n_key.as_ref().map(|i18n_key| format!("i18n: {}", i18n_key));
+        match (&self.tooltip, i18n_key) {
+            (Some(tooltip), Some(i18n_key)) => Some(format!("{}\n{}", tooltip, i18n_key)),
+            (Some(tooltip), None) => Some(tooltip.clone()),
+            (None, i18n_key) => i18n_key,
+        }
+    }
+
+    // Check a numeric arg value against the `min`/`max` bounds
+    fn check_bounds(&self, value: f64) -> Result<(), String> {
+        let in_bounds = self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max);
+        if in_bounds {
+            return Ok(());
+        }
+
+        Err(match (self.min, self.max) {
+            (Some(min), Some(max)) => format!("Field '{}' must be between {} and {}", self.name, min, max),
+            (Some(min), None) => format!("Field '{}' must be at least {}", self.name, min),
+            (None, Some(max)) => format!("Field '{}' must be at most {}", self.name, max),
+            (None, None) => return Ok(()),
+        })
+    }
+
+    // Check a text arg value against `min_length`
+    fn check_length(&self, value: &str) -> Result<(), String> {
+        match self.min_length {
+            Some(min_length) if value.chars().count() < min_length => Err(format!(
+                "Field '{}' must be at least {} characters long",
+                self.name, min_length
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Builder for `ArgType`, for stories that implement `StoryMeta` by hand
+///
+/// ```ignore
+/// ArgTypeBuilder::new("label")
+///     .control(ControlType::Text)
+///     .default("'hello'")
+///     .required(true)
+///     .description("The label text")
+///     .build()?
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArgTypeBuilder {
+    arg_type: ArgType,
+}
+
+impl ArgTypeBuilder {
+    /// Start an optional text arg with the given field name
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            arg_type: ArgType {
+                name: name.into(),
+                default_value: None,
+                control: ControlType::Text,
+                required: false,
+                options: None,
+                description: None,
+                min: None,
+                max: None,
+                i18n_key: None,
+                tooltip: None,
+                option_groups: None,
+                textarea_rows: None,
+                textarea_resize: None,
+                min_length: None,
+                linked_field: None,
+                number_format: None,
+                number_currency: None,
+                display_format: None,
+                custom_control: None,
+                radio_icons: None,
+                allow_custom_value: false,
+                depends_on: Vec::new(),
+            },
+        }
+    }
+
+    /// Set the control used to edit the arg
+    pub fn control(mut self, control: ControlType) -> Self {
+        self.arg_type.control = control;
+        self
+    }
+
+    /// Set the default value, as a JS expression, e.g. `'hello'` or `42`
+    pub fn default(mut self, default_value: impl Into<String>) -> Self {
+        self.arg_type.default_value = Some(default_value.into());
+        self
+    }
+
+    /// Set whether the arg is listed as required
+    pub fn required(mut self, required: bool) -> Self {
+        self.arg_type.required = required;
+        self
+    }
+
+    /// Set the description shown in the docs table
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.arg_type.description = Some(description.into());
+        self
+    }
+
+    /// Set the options of a select control
+    pub fn options<S: Into<String>>(mut self, options: impl IntoIterator<Item = S>) -> Self {
+        self.arg_type.options = Some(options.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the lowest accepted value of a number or range control
+    pub fn min(mut self, min: f64) -> Self {
+        self.arg_type.min = Some(min);
+        self
+    }
+
+    /// Set the highest accepted value of a number or range control
+    pub fn max(mut self, max: f64) -> Self {
+        self.arg_type.max = Some(max);
+        self
+    }
+
+    /// Set the translation key the arg's value comes from
+    pub fn i18n_key(mut self, i18n_key: impl Into<String>) -> Self {
+        self.arg_type.i18n_key = Some(i18n_key.into());
+        self
+    }
+
+    /// Set the help shown in a tooltip on the arg's type in the docs table; `\n` starts a new line
+    pub fn tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.arg_type.tooltip = Some(tooltip.into());
+        self
+    }
+
+    /// Group select options under headings, as `(group name, options)`
+    pub fn option_groups(mut self, option_groups: Vec<(String, Vec<String>)>) -> Self {
+        self.arg_type.option_groups = Some(option_groups);
+        self
+    }
+
+    /// Set the visible rows of a text control's textarea
+    pub fn textarea_rows(mut self, rows: usize) -> Self {
+        self.arg_type.textarea_rows = Some(rows);
+        self
+    }
+
+    /// Set the CSS `resize` mode of a text control's textarea
+    pub fn textarea_resize(mut self, resize: &'static str) -> Self {
+        self.arg_type.textarea_resize = Some(resize);
+        self
+    }
+
+    /// Set the fewest characters a text control accepts
+    pub fn min_length(mut self, min_length: usize) -> Self {
+        self.arg_type.min_length = Some(min_length);
+        self
+    }
+
+    /// Keep another number arg at `ratio` (this value / that value) to this one, e.g. 16/9 for width and height
+    pub fn linked_to(mut self, field: impl Into<String>, ratio: f64) -> Self {
+        self.arg_type.linked_field = Some((field.into(), ratio));
+        self
+    }
+
+    /// Display a number control's value as an amount in the given currency, e.g. `USD`
+    pub fn currency(mut self, currency: impl Into<String>) -> Self {
+        self.arg_type.number_format = Some("currency".to_string());
+        self.arg_type.number_currency = Some(currency.into());
+        self
+    }
+
+    /// Show a `0.0`-`1.0` number control's value as a percentage from 0 to 100, e.g. for opacity
+    pub fn percentage(mut self) -> Self {
+        self.arg_type.display_format = Some("percentage".to_string());
+        self
+    }
+
+    /// Edit the arg with a control component registered in preview.js, e.g. `GradientPicker`
+    pub fn custom_control(mut self, component: impl Into<String>) -> Self {
+        self.arg_type.control = ControlType::Custom;
+        self.arg_type.custom_control = Some(component.into());
+        self
+    }
+
+    /// Label an inline radio's options with icons, e.g. `["←", "↔", "→"]`
+    pub fn radio_icons<S: Into<String>>(mut self, icons: impl IntoIterator<Item = S>) -> Self {
+        self.arg_type.radio_icons = Some(icons.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Let a select also accept values typed in, with its options as suggestions
+    pub fn allow_custom_value(mut self, allow_custom_value: bool) -> Self {
+        self.arg_type.allow_custom_value = allow_custom_value;
+        self
+    }
+
+    /// Set the args whose values a select's options depend on
+    pub fn depends_on<S: Into<String>>(mut self, fields: impl IntoIterator<Item = S>) -> Self {
+        self.arg_type.depends_on = fields.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Check the settings fit together and build the `ArgType`
+    pub fn build(self) -> Result<ArgType, ArgTypeError> {
+        let arg_type = self.arg_type;
+        let is_select = matches!(arg_type.control, ControlType::Select | ControlType::InlineRadio);
+        let is_numeric = matches!(arg_type.control, ControlType::Number | ControlType::Range);
+        let is_text = matches!(arg_type.control, ControlType::Text);
+
+        if arg_type.name.is_empty() {
+            return Err(ArgTypeError::EmptyName);
+        }
+        let has_select_settings = arg_type.options.is_some()
+            || arg_type.option_groups.is_some()
+            || !arg_type.depends_on.is_empty()
+            || arg_type.allow_custom_value;
+        if !is_select && has_select_settings {
+            return Err(ArgTypeError::OptionsWithoutSelect(arg_type.name));
+        }
+        let has_number_settings = arg_type.min.is_some()
+            || arg_type.max.is_some()
+            || arg_type.number_format.is_some()
+            || arg_type.display_format.is_some()
+            || arg_type.linked_field.is_some();
+        if !is_numeric && has_number_settings {
+            return Err(ArgTypeError::BoundsWithoutNumber(arg_type.name));
+        }
+        if let (Some(min), Some(max)) = (arg_type.min, arg_type.max) {
+            if min > max {
+                return Err(ArgTypeError::InvalidBounds(arg_type.name));
+            }
+        }
+        if !is_text && (arg_type.textarea_rows.is_some() || arg_type.textarea_resize.is_some()) {
+            return Err(ArgTypeError::TextareaWithoutText(arg_type.name));
+        }
+        if !is_text && arg_type.min_length.is_some() {
+            return Err(ArgTypeError::LengthWithoutText(arg_type.name));
+        }
+        if !matches!(arg_type.control, ControlType::InlineRadio) && arg_type.radio_icons.is_some() {
+            return Err(ArgTypeError::IconsWithoutRadio(arg_type.name));
+        }
+
+        Ok(arg_type)
+    }
+}
+
+/// Why an `ArgTypeBuilder` couldn't build its `ArgType`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgTypeError {
+    /// The arg has no field name
+    EmptyName,
+    /// Options, option groups, `depends_on` or custom values were set on a control that isn't a select
+    OptionsWithoutSelect(String),
+    /// `min`, `max`, a number or display format or a linked field were set on a control that isn't a number or range
+    BoundsWithoutNumber(String),
+    /// `min` is greater than `max`
+    InvalidBounds(String),
+    /// Textarea settings were set on a control that isn't text
+    TextareaWithoutText(String),
+    /// `min_length` was set on a control that isn't text
+    LengthWithoutText(String),
+    /// Radio icons were set on a control that isn't an inline radio
+    IconsWithoutRadio(String),
+}
+
+impl std::fmt::Display for ArgTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgTypeError::EmptyName => write!(f, "arg type has no name"),
+            ArgTypeError::OptionsWithoutSelect(name) => write!(f, "arg '{}' has options but no select control", name),
+            ArgTypeError::BoundsWithoutNumber(name) => write!(f, "arg '{}' has number settings but no number or range control", name),
+            ArgTypeError::InvalidBounds(name) => write!(f, "arg '{}' has a min greater than its max", name),
+            ArgTypeError::TextareaWithoutText(name) => write!(f, "arg '{}' has textarea settings but no text control", name),
+            ArgTypeError::LengthWithoutText(name) => write!(f, "arg '{}' has a min_length but no text control", name),
+            ArgTypeError::IconsWithoutRadio(name) => write!(f, "arg '{}' has radio icons but no inline-radio control", name),
+        }
+    }
 }
 
+impl std::error::Error for ArgTypeError {}
+
 /// Story trait that components must implement
 ///
 /// Components can implement this trait and return any type that converts to Dom.
@@ -41,6 +436,200 @@ pub trait StoryMeta: Sized {
     type StoryArgs: for<'de> Deserialize<'de> + Into<Self>;
     fn name() -> &'static str;
     fn args() -> Vec<ArgType>;
+
+    /// Render the story with the given args and mount it into `container`
+    ///
+    /// Panics if the args don't deserialize into `StoryArgs`.
+    fn render_to_dom(args: JsValue, container: &web_sys::Element)
+    where
+        Self: Story,
+    {
+        let component: Self::StoryArgs = serde_wasm_bindgen::from_value(args)
+            .unwrap_or_else(|err| panic!("Invalid args for story '{}': {}", Self::name(), err));
+        let story: Self = component.into();
+        if let Some(handles) = story.live_handles() {
+            STORY_INSTANCES.with(|instances| instances.borrow_mut().insert(Self::name().to_string(), handles));
+        }
+        dominator::append_dom(container, story.to_story());
+    }
+
+    /// Handles to the story's `Mutable` fields, kept so `update_story_arg` can update it in place
+    fn live_handles(&self) -> Option<Box<dyn std::any::Any>> {
+        None
+    }
+
+    /// Function updating a rendered story from new args, for stories with live controls
+    fn live_update_fn() -> Option<LiveUpdateFn> {
+        None
+    }
+
+    /// Fixed canvas width for the story, e.g. `300px`
+    fn canvas_width() -> Option<&'static str> {
+        None
+    }
+
+    /// Fixed canvas height for the story, e.g. `200px`
+    fn canvas_height() -> Option<&'static str> {
+        None
+    }
+
+    /// CSS padding around the story's content, e.g. `10px 20px`
+    fn canvas_padding() -> Option<&'static str> {
+        None
+    }
+
+    /// Storybook viewport preset the story opens in, e.g. `iphone6`
+    fn default_viewport() -> Option<&'static str> {
+        None
+    }
+
+    /// Title the story is listed under in Storybook's sidebar, which its ID is derived from
+    fn title() -> String {
+        format!("Components/{}", Self::name())
+    }
+
+    /// Storybook component ID used in URL routing, overriding the one derived from the title
+    fn component_id() -> Option<&'static str> {
+        None
+    }
+
+    /// Export name of the story's main variant in the `.stories.js` file
+    fn primary_variant_name() -> &'static str {
+        "Default"
+    }
+
+    /// Delay before the story's play function interacts with it, in milliseconds
+    fn interact_delay_ms() -> Option<u32> {
+        None
+    }
+
+    /// Component description shown at the top of the Docs page
+    fn component_docs() -> Option<&'static str> {
+        None
+    }
+
+    /// Markdown note shown in the Notes addon
+    fn note() -> Option<&'static str> {
+        None
+    }
+
+    /// `file:line` of the Rust definition of the story
+    fn source_location() -> Option<&'static str> {
+        None
+    }
+
+    /// Expected accessible name of the story's root element
+    fn aria_label() -> Option<&'static str> {
+        None
+    }
+
+    /// Whether render panics are shown in place of the story instead of crashing it
+    fn error_boundary() -> bool {
+        false
+    }
+
+    /// Whether the story is shown side by side at mobile and desktop widths
+    fn responsive_preview() -> bool {
+        false
+    }
+
+    /// Whether a counter of how often the story has rendered is shown over it
+    fn track_renders() -> bool {
+        false
+    }
+
+    /// Whether the story is only rendered once, with later renders reusing the first container
+    fn render_once() -> bool {
+        false
+    }
+
+    /// Element the story is mounted into instead of its container, e.g. `#story-portal` for modals
+    fn portal_target() -> Option<&'static str> {
+        None
+    }
+
+    /// Whether the story is captured by the script from `generate_story_screenshots_script`
+    fn screenshot() -> bool {
+        false
+    }
+
+    /// Whether the controls panel starts expanded, showing descriptions and defaults, or Storybook's default if `None`
+    fn controls_expanded() -> Option<bool> {
+        None
+    }
+
+    /// Columns shown in the Docs args table, or all of them if empty
+    fn arg_table_columns() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Feature flags the story is behind, shown as badges in Storybook
+    fn feature_flags() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Arg types of named variants whose controls differ from the main variant's
+    fn variant_args() -> std::collections::HashMap<&'static str, fn() -> Vec<ArgType>> {
+        std::collections::HashMap::new()
+    }
+
+    /// Names of JS decorators wrapping the story, e.g. `withRouter`
+    fn decorators() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Names of the stories this story's component is built from
+    fn dependencies() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Stories shown side by side by a `#[derive(StoryCompose)]` story
+    fn composed_stories() -> &'static [compose::ComposeRegistration] {
+        &[]
+    }
+
+    /// The template the story's `.stories.js` file was written from, if it was written while compiling
+    fn story_template() -> Option<template::StoryTemplate> {
+        None
+    }
+
+    /// Keyboard shortcuts the component handles, as `(key, action)`
+    fn keyboard_shortcuts() -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// Select options taken from `options_const` arrays, as `(field name, options)`
+    fn const_options() -> Vec<(&'static str, &'static [&'static str])> {
+        Vec::new()
+    }
+
+    /// Select options of enums from other crates, given with `external_options`, as `(field name, options)`
+    fn external_options() -> Vec<(&'static str, &'static [&'static str])> {
+        Vec::new()
+    }
+
+    /// Functions returning select options, given with `options_fn`, as `(field name, function)`
+    fn options_fns() -> Vec<(&'static str, OptionsFn)> {
+        Vec::new()
+    }
+
+    /// Register the `StorySelect` enums of the story's select fields, so their options don't wait for `init_enums()`
+    fn register_select_enums() {}
+
+    /// Functions computing select options from the story's current args, keyed by field name
+    fn dependent_options() -> std::collections::HashMap<String, DependentOptionsFn> {
+        std::collections::HashMap::new()
+    }
+
+    /// Preview functions for args with an inline control, keyed by field name
+    fn inline_previews() -> std::collections::HashMap<String, InlinePreviewFn> {
+        std::collections::HashMap::new()
+    }
+
+    /// Renderers for args with a custom control, keyed by field name
+    fn custom_controls() -> std::collections::HashMap<String, controls::CustomControlFn> {
+        std::collections::HashMap::new()
+    }
 }
 
 /// Extension trait for types that can be converted to stories
@@ -61,145 +650,1256 @@ impl IntoStory for Dom {
 
 /// StorySelect trait for enums that should appear as select controls
 pub trait StorySelect: 'static {
-    /// Get the enum type name
+    /// Get the name the enum is registered under, its full path with `#[story_select(use_qualified_name)]`
     fn type_name() -> &'static str;
     
     /// Get all possible values as strings
     fn options() -> Vec<String>;
+
+    /// Get the initial value shown in the select, the `#[default]` variant if there is one
+    ///
+    /// Defaults to the first of `options()`, or an empty string if there are none
+    fn default_option() -> &'static str {
+        // Options are owned strings, so the first one is leaked once per enum to hand out as 'static
+        static FIRST_OPTIONS: Lazy<Mutex<std::collections::HashMap<std::any::TypeId, &'static str>>> =
+            Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
+        FIRST_OPTIONS
+            .lock()
+            .unwrap()
+            .entry(std::any::TypeId::of::<Self>())
+            .or_insert_with(|| Self::options().into_iter().next().map_or("", |option| &*Box::leak(option.into_boxed_str())))
+    }
+
+    /// Options grouped under a heading, as `(group name, options)`, shown as `group / option` in the select
+    fn grouped_options() -> Option<Vec<(&'static str, Vec<&'static str>)>> {
+        None
+    }
+
+    /// Numeric discriminants of a `#[repr(u8)]`/`u16`/`u32` enum, in the same order as `options()`
+    fn discriminants() -> Option<Vec<i64>> {
+        None
+    }
+
+    /// Get the variant with the given discriminant, for `#[repr(u8)]`/`u16`/`u32` enums
+    fn from_discriminant(_value: i64) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Add the enum to the registry read by `get_enum_options` and the other enum getters
+    ///
+    /// Derived enums are only registered the first time this is called, when a story with a
+    /// select over them is registered or `init_enums()` runs, whichever comes first.
+    fn register()
+    where
+        Self: Sized,
+    {
+        register_select_enum::<Self>();
+    }
+}
+
+/// Register an enum's options, default, groups and discriminants with the global registry
+#[doc(hidden)]
+pub fn register_select_enum<T: StorySelect>() {
+    let type_name = T::type_name();
+    register_enum_options(type_name, T::options());
+    register_enum_default(type_name, T::default_option());
+    if let Some(groups) = T::grouped_options() {
+        register_enum_groups(type_name, groups);
+    }
+    if let Some(discriminants) = T::discriminants() {
+        register_enum_discriminants(type_name, discriminants);
+    }
 }
 
 /// Story metadata for registration
+///
+/// Only holds `'static` data, function pointers and `Send + Sync` closures, so it is
+/// `Send + Sync` without any `unsafe`. The `Dom` built by `render_to_dom_fn` is not
+/// thread-safe, but it is created and mounted on the calling thread and never stored in
+/// the registry. Derived stories fill the closures in from `StoryMeta`, and stories
+/// configured at runtime from `meta::StoryMetaBuilder`.
+#[derive(Clone)]
 pub struct StoryRegistration {
     pub name: &'static str,
-    pub args: fn() -> Vec<ArgType>,
-    pub render_fn: fn(JsValue) -> Dom,
+    pub title: String,
+    pub args: ArgsFn,
+    pub variant_args: std::collections::HashMap<&'static str, fn() -> Vec<ArgType>>,
+    pub render_to_dom_fn: RenderToDomFn,
+    pub canvas_width: Option<&'static str>,
+    pub canvas_height: Option<&'static str>,
+    pub canvas_padding: Option<&'static str>,
+    pub default_viewport: Option<&'static str>,
+    pub component_id: Option<&'static str>,
+    pub primary_variant_name: &'static str,
+    pub interact_delay_ms: Option<u32>,
+    pub component_docs: Option<&'static str>,
+    pub note: Option<&'static str>,
+    pub source_location: Option<&'static str>,
+    pub aria_label: Option<&'static str>,
+    pub error_boundary: bool,
+    pub track_renders: bool,
+    pub render_once: bool,
+    pub portal_target: Option<&'static str>,
+    pub screenshot: bool,
+    pub arg_table_columns: &'static [&'static str],
+    pub controls_expanded: Option<bool>,
+    pub feature_flags: &'static [&'static str],
+    pub decorators: &'static [&'static str],
+    pub dependencies: &'static [&'static str],
+    pub composed_stories: &'static [compose::ComposeRegistration],
+    pub story_template: fn() -> Option<template::StoryTemplate>,
+    pub keyboard_shortcuts: &'static [(&'static str, &'static str)],
+    pub inline_previews: std::collections::HashMap<String, InlinePreviewFn>,
+    pub dependent_options: std::collections::HashMap<String, DependentOptionsFn>,
+    pub update_fn: Option<LiveUpdateFn>,
 }
 
-unsafe impl Sync for StoryRegistration {}
+/// Columns of the Docs args table that `#[story(arg_table_columns = "...")]` can pick from
+pub const ARG_TABLE_COLUMNS: &[&str] = &["name", "type", "default", "description", "required", "control"];
+
+impl StoryRegistration {
+    /// Columns of the Docs args table hidden for this story
+    pub fn hidden_arg_table_columns(&self) -> Vec<&'static str> {
+        if self.arg_table_columns.is_empty() {
+            return Vec::new();
+        }
+        ARG_TABLE_COLUMNS
+            .iter()
+            .copied()
+            .filter(|column| !self.arg_table_columns.contains(column))
+            .collect()
+    }
+
+    /// `parameters.controls.expanded` for this story, `false` if the description and default columns are hidden
+    pub fn controls_expanded_param(&self) -> Option<bool> {
+        if self.hidden_arg_table_columns().contains(&"description") {
+            return Some(false);
+        }
+        self.controls_expanded
+    }
+
+    /// Hide this story's hidden args table columns on one of its `argTypes` entries
+    ///
+    /// The type is hidden with `table.type.summary: null`, the required marker by leaving out the
+    /// arg's category and the control with `control: false`. The description and default columns
+    /// are hidden for the whole story instead, see `controls_expanded_param`.
+    pub fn hide_arg_table_columns(&self, arg_type: &mut serde_json::Map<String, serde_json::Value>) {
+        let hidden_columns = self.hidden_arg_table_columns();
+        if hidden_columns.contains(&"control") {
+            arg_type.insert("control".to_string(), serde_json::Value::Bool(false));
+        }
+        let Some(serde_json::Value::Object(table)) = arg_type.get_mut("table") else {
+            if hidden_columns.contains(&"type") {
+                arg_type.insert("table".to_string(), serde_json::json!({ "type": { "summary": null } }));
+            }
+            return;
+        };
+        if hidden_columns.contains(&"type") {
+            table.insert("type".to_string(), serde_json::json!({ "summary": null }));
+        }
+        if hidden_columns.contains(&"required") {
+            table.remove("category");
+        }
+    }
+}
 
 // Global registry for stories
 static STORY_REGISTRY: Lazy<Mutex<Vec<StoryRegistration>>> = Lazy::new(|| Mutex::new(Vec::new()));
 
-// Global registry for enum options
-static ENUM_REGISTRY: Lazy<Mutex<std::collections::HashMap<String, Vec<String>>>> = 
+// Invariant violations reported by the last render of each story
+static STORY_ERRORS: Lazy<Mutex<std::collections::HashMap<String, Vec<String>>>> =
     Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
 
-/// Register a story with the global registry
-#[doc(hidden)]
-pub fn register_story<T: Story + StoryMeta>() {
-    let registration = StoryRegistration {
-        name: T::name(),
-        args: T::args,
-        render_fn: |args: JsValue| {
-            let component: T::StoryArgs = serde_wasm_bindgen::from_value(args).unwrap();
-            let story: T = component.into();
-            story.to_story()
-        },
-    };
-    STORY_REGISTRY.lock().unwrap().push(registration);
-}
+// Number of times each story with `track_renders` has been rendered
+static RENDER_COUNTS: Lazy<Mutex<std::collections::HashMap<String, u32>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
 
-/// Register an enum's options with the global registry
-#[doc(hidden)]
-pub fn register_enum_options(type_name: &'static str, options: Vec<String>) {
-    web_sys::console::log_1(&format!("Registering enum {}: {:?}", type_name, options).into());
-    ENUM_REGISTRY.lock().unwrap().insert(type_name.to_string(), options);
+thread_local! {
+    // `Mutable` handles or reactive args of the last render of each story with live controls
+    static STORY_INSTANCES: std::cell::RefCell<std::collections::HashMap<String, Box<dyn std::any::Any>>> =
+        std::cell::RefCell::new(std::collections::HashMap::new());
+
+    // Containers of mounted `#[story(once)]` stories, handed out again instead of re-rendering
+    static RENDERED_ONCE: std::cell::RefCell<std::collections::HashMap<String, web_sys::Element>> =
+        std::cell::RefCell::new(std::collections::HashMap::new());
+
+    // Name of the story currently being rendered, so `story_invariant!` knows where to report
+    static CURRENT_STORY: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
 }
 
-/// Get enum options for a given type name
-#[wasm_bindgen]
-pub fn get_enum_options(type_name: &str) -> JsValue {
-    let registry = ENUM_REGISTRY.lock().unwrap();
-    web_sys::console::log_1(&format!("Getting enum options for {}, registry has {} entries", type_name, registry.len()).into());
-    if let Some(options) = registry.get(type_name) {
-        web_sys::console::log_1(&format!("Found options: {:?}", options).into());
-        serde_wasm_bindgen::to_value(options).unwrap_or(JsValue::NULL)
-    } else {
-        web_sys::console::log_1(&format!("No options found for {}", type_name).into());
-        JsValue::NULL
+// Marks a story as being rendered until dropped, even if rendering panics
+struct CurrentStoryGuard;
+
+impl CurrentStoryGuard {
+    fn enter(name: &str) -> Self {
+        CURRENT_STORY.with(|current| *current.borrow_mut() = Some(name.to_string()));
+        STORY_ERRORS.lock().unwrap().remove(name);
+        event_log::start_story(name);
+        CurrentStoryGuard
     }
 }
 
-/// Macro to help register stories - used by derive macro
-#[macro_export]
-macro_rules! __register_story {
-    ($ty:ty) => {{
-        $crate::register_story($crate::StoryMeta {
-            name: <$ty as $crate::Story>::name(),
-            args: <$ty as $crate::Story>::args,
-            render_fn: <$ty as $crate::Story>::render,
-        });
-    }};
+impl Drop for CurrentStoryGuard {
+    fn drop(&mut self) {
+        CURRENT_STORY.with(|current| *current.borrow_mut() = None);
+    }
 }
 
-/// Get all registered stories as Storybook-compatible format
-#[wasm_bindgen]
-pub fn get_stories() -> JsValue {
-    let stories = STORY_REGISTRY.lock().unwrap();
-    let story_defs: Vec<_> = stories
-        .iter()
-        .map(|meta| {
-            let args = (meta.args)();
-            let mut arg_types = serde_json::Map::new();
-            let mut default_args = serde_json::Map::new();
-
-            for arg in args {
-                let control = serde_json::to_value(&arg.control).unwrap();
-
-                let mut table = std::collections::HashMap::new();
-                if arg.required {
-                    table.insert("category".to_string(), "required".to_string());
-                } else {
-                    table.insert("category".to_string(), "optional".to_string());
-                }
+// Options of an `options_const` select, e.g. `&["light", "dark"]`
+type ConstOptions = &'static [&'static str];
 
-                let mut arg_map = serde_json::Map::new();
-                arg_map.insert("name".to_string(), serde_json::Value::String(arg.name.clone()));
-                arg_map.insert("control".to_string(), control);
-                arg_map.insert("table".to_string(), serde_json::to_value(table).unwrap());
+// Global registry for `options_const` select options, keyed by story and field name
+static CONST_REGISTRY: Lazy<Mutex<std::collections::HashMap<(String, String), ConstOptions>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
 
-                if let Some(default) = arg.default_value {
-                    default_args.insert(arg.name.clone(), serde_json::Value::String(default));
-                }
+// Global registry for enum options
+static ENUM_REGISTRY: Lazy<Mutex<std::collections::HashMap<String, Vec<String>>>> = 
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
 
-                arg_types.insert(arg.name, serde_json::Value::Object(arg_map));
-            }
+// Global registry for enum option groups, mapping each option to its group name
+static ENUM_GROUP_REGISTRY: Lazy<Mutex<std::collections::HashMap<String, std::collections::HashMap<String, String>>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
 
-            serde_json::json!({
-                "name": meta.name,
-                "argTypes": arg_types,
-                "args": default_args,
-            })
-        })
+// Global registry for the numeric discriminants of `#[repr(u8)]` enums
+static ENUM_DISCRIMINANT_REGISTRY: Lazy<Mutex<std::collections::HashMap<String, Vec<i64>>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
+
+// Global registry for enum default options
+static ENUM_DEFAULT_REGISTRY: Lazy<Mutex<std::collections::HashMap<String, &'static str>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
+
+/// Deserialize a select control value into an enum
+///
+/// Accepts the variant name, or the numeric discriminant for `#[repr(u8)]` enums.
+#[doc(hidden)]
+pub fn deserialize_select<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: StorySelect + serde::de::DeserializeOwned,
+{
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::Number(number)) => {
+            let discriminant = number
+                .as_i64()
+                .or_else(|| number.as_f64().map(|value| value as i64))
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid {} discriminant {}", T::type_name(), number)))?;
+            T::from_discriminant(discriminant).map(Some).ok_or_else(|| {
+                serde::de::Error::custom(format!("no {} variant with discriminant {}", T::type_name(), discriminant))
+            })
+        }
+        Some(value) => serde_json::from_value(value).map(Some).map_err(serde::de::Error::custom),
+    }
+}
+
+/// Deserialize a switch control value into a boolean
+///
+/// Switch controls map `true`/`false` to `'on'`/`'off'`, so both forms are accepted.
+#[doc(hidden)]
+pub fn deserialize_switch<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: From<bool>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SwitchValue {
+        Bool(bool),
+        Str(String),
+    }
+
+    let value = match Option::<SwitchValue>::deserialize(deserializer)? {
+        Some(SwitchValue::Bool(value)) => value,
+        Some(SwitchValue::Str(value)) => match value.as_str() {
+            "on" | "true" => true,
+            "off" | "false" => false,
+            other => {
+                return Err(serde::de::Error::custom(format!("invalid switch value '{}'", other)))
+            }
+        },
+        None => false,
+    };
+
+    Ok(T::from(value))
+}
+
+/// Parse a color control value into RGBA bytes, for `color_type = "rgba_bytes"` fields
+///
+/// Accepts `rgba(R, G, B, A)`, `rgb(R, G, B)`, `#rrggbb` and `#rrggbbaa`. An alpha above 1 is
+/// read as a byte, otherwise as the CSS fraction the color picker produces, e.g. `0.5`.
+///
+/// ```ignore
+/// assert_eq!(storybook::parse_rgba_string("rgba(255, 0, 0, 0.5)"), Some([255, 0, 0, 128]));
+/// ```
+pub fn parse_rgba_string(value: &str) -> Option<[u8; 4]> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+            return None;
+        }
+        let mut bytes = [255; 4];
+        for (byte, i) in bytes.iter_mut().zip((0..hex.len()).step_by(2)) {
+            *byte = u8::from_str_radix(&hex[i..i + 2], 16).ok()?;
+        }
+        return Some(bytes);
+    }
+
+    let channels = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))?
+        .strip_suffix(')')?;
+    let channels: Vec<f64> = channels
+        .split(',')
+        .map(|channel| channel.trim().parse::<f64>().ok())
+        .collect::<Option<_>>()?;
+    let (rgb, alpha) = match channels.as_slice() {
+        [r, g, b] => ([*r, *g, *b], 255.0),
+        [r, g, b, a] if *a > 1.0 => ([*r, *g, *b], *a),
+        [r, g, b, a] => ([*r, *g, *b], a * 255.0),
+        _ => return None,
+    };
+
+    let [r, g, b] = rgb.map(|channel| channel.round().clamp(0.0, 255.0) as u8);
+    Some([r, g, b, alpha.round().clamp(0.0, 255.0) as u8])
+}
+
+/// Parse the selected option of an `external_options` select, falling back to the first option that parses
+///
+/// Values that aren't one of the options are reported by the `OneOf` validator before the story
+/// renders. Panics only if none of the options themselves parse, as the field would have no value.
+#[doc(hidden)]
+pub fn parse_external_option<T: std::str::FromStr>(value: Option<&str>, options: &[&str]) -> T {
+    value
+        .into_iter()
+        .chain(options.iter().copied())
+        .find_map(|option| option.parse().ok())
+        .unwrap_or_else(|| panic!("none of the options {:?} parse as {}", options, std::any::type_name::<T>()))
+}
+
+/// Register a story with the global registry
+///
+/// Registering the same story name twice (e.g. `register_all_stories()` running once
+/// per test module) keeps the first registration and logs a warning.
+#[doc(hidden)]
+pub fn register_story<T: Story + StoryMeta>() {
+    let registration = StoryRegistration {
+        name: T::name(),
+        title: T::title(),
+        args: Arc::new(T::args),
+        variant_args: T::variant_args(),
+        render_to_dom_fn: if T::responsive_preview() {
+            Arc::new(helpers::render_responsive::<T>)
+        } else {
+            Arc::new(T::render_to_dom)
+        },
+        canvas_width: T::canvas_width(),
+        canvas_height: T::canvas_height(),
+        canvas_padding: T::canvas_padding(),
+        default_viewport: T::default_viewport(),
+        component_id: T::component_id(),
+        primary_variant_name: T::primary_variant_name(),
+        interact_delay_ms: T::interact_delay_ms(),
+        component_docs: T::component_docs(),
+        note: T::note(),
+        source_location: T::source_location(),
+        aria_label: T::aria_label(),
+        error_boundary: T::error_boundary(),
+        track_renders: T::track_renders(),
+        render_once: T::render_once(),
+        portal_target: T::portal_target(),
+        screenshot: T::screenshot(),
+        arg_table_columns: T::arg_table_columns(),
+        controls_expanded: T::controls_expanded(),
+        feature_flags: T::feature_flags(),
+        decorators: T::decorators(),
+        dependencies: T::dependencies(),
+        composed_stories: T::composed_stories(),
+        story_template: T::story_template,
+        keyboard_shortcuts: T::keyboard_shortcuts(),
+        inline_previews: T::inline_previews(),
+        dependent_options: T::dependent_options(),
+        update_fn: T::live_update_fn(),
+    };
+    if !add_story(registration) {
+        console_warn(&format!("Story '{}' is already registered, skipping duplicate", T::name()));
+        return;
+    }
+
+    T::register_select_enums();
+
+    let mut const_registry = CONST_REGISTRY.lock().unwrap();
+    for (field_name, options) in T::const_options() {
+        const_registry.insert((T::name().to_string(), field_name.to_string()), options);
+    }
+    drop(const_registry);
+
+    // External enums and `options_fn` selects have no type registration, so their options are kept under `Story::field`
+    for (field_name, options) in T::external_options() {
+        let type_name = format!("{}::{}", T::name(), field_name);
+        ENUM_REGISTRY.lock().unwrap().insert(type_name.clone(), options.iter().map(|option| option.to_string()).collect());
+        if let Some(default_option) = options.first() {
+            ENUM_DEFAULT_REGISTRY.lock().unwrap().insert(type_name, default_option);
+        }
+        controls::register_story_validator(T::name(), field_name, <controls::OneOf as controls::ControlValidator>::validate);
+    }
+    for (field_name, options_fn) in T::options_fns() {
+        ENUM_REGISTRY.lock().unwrap().insert(format!("{}::{}", T::name(), field_name), options_fn());
+    }
+
+    controls::register(T::name(), T::custom_controls());
+}
+
+// Add a story to the registry, unless one with the same name is already there
+//
+// Checked under the same lock as the push, so two registrations racing can't both get in
+pub(crate) fn add_story(registration: StoryRegistration) -> bool {
+    let mut stories = STORY_REGISTRY.lock().unwrap();
+    if stories.iter().any(|meta| meta.name == registration.name) {
+        return false;
+    }
+    stories.push(registration);
+    true
+}
+
+// Copy what's needed out of a registered story with `f`. The registry is unlocked again before
+// anything returned is called, as args functions, renders and callbacks may look up stories themselves
+pub(crate) fn with_story<R>(name: &str, f: impl FnOnce(&StoryRegistration) -> R) -> Option<R> {
+    STORY_REGISTRY.lock().unwrap().iter().find(|meta| meta.name == name).map(f)
+}
+
+// The args function of a registered story, see `with_story`
+pub(crate) fn story_args_fn(name: &str) -> Option<ArgsFn> {
+    with_story(name, |meta| meta.args.clone())
+}
+
+// Copy what's needed out of every registered story `f` keeps, like `with_story`
+pub(crate) fn collect_stories<R>(f: impl FnMut(&StoryRegistration) -> Option<R>) -> Vec<R> {
+    STORY_REGISTRY.lock().unwrap().iter().filter_map(f).collect()
+}
+
+// Log to the browser console. Registration also runs natively, e.g. for `storybook-codegen`,
+// where there's no console and making a `JsValue` panics, so there it's skipped
+#[cfg(target_arch = "wasm32")]
+fn console_log(message: &str) {
+    web_sys::console::log_1(&message.into());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn console_log(_message: &str) {}
+
+// Warn in the browser console, or on stderr natively, see `console_log`
+#[cfg(target_arch = "wasm32")]
+fn console_warn(message: &str) {
+    web_sys::console::warn_1(&message.into());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn console_warn(message: &str) {
+    eprintln!("{}", message);
+}
+
+/// Copy of every registered story, for tools generating files from the registry
+pub fn story_registrations() -> Vec<StoryRegistration> {
+    collect_stories(|meta| Some(meta.clone()))
+}
+
+/// Register an enum's options with the global registry
+#[doc(hidden)]
+pub fn register_enum_options(type_name: &'static str, options: Vec<String>) {
+    console_log(&format!("Registering enum {}: {:?}", type_name, options));
+    ENUM_REGISTRY.lock().unwrap().insert(type_name.to_string(), options);
+}
+
+/// Register an enum's default option with the global registry
+#[doc(hidden)]
+pub fn register_enum_default(type_name: &'static str, default_option: &'static str) {
+    ENUM_DEFAULT_REGISTRY.lock().unwrap().insert(type_name.to_string(), default_option);
+}
+
+/// Register an enum's option groups with the global registry
+#[doc(hidden)]
+pub fn register_enum_groups(type_name: &'static str, groups: Vec<(&'static str, Vec<&'static str>)>) {
+    let option_groups = groups
+        .into_iter()
+        .flat_map(|(group_name, options)| {
+            options
+                .into_iter()
+                .map(move |option| (option.to_string(), group_name.to_string()))
+        })
+        .collect();
+    ENUM_GROUP_REGISTRY.lock().unwrap().insert(type_name.to_string(), option_groups);
+}
+
+/// Register the numeric discriminants of a `#[repr(u8)]` enum with the global registry
+#[doc(hidden)]
+pub fn register_enum_discriminants(type_name: &'static str, discriminants: Vec<i64>) {
+    ENUM_DISCRIMINANT_REGISTRY.lock().unwrap().insert(type_name.to_string(), discriminants);
+}
+
+// Find the name an enum is registered under from its type as written in a story field, e.g.
+// `button::ButtonSize`. Enums with `use_qualified_name` are registered under their full path,
+// so a path ending in the written one matches too, as long as only one does.
+fn registered_enum_name(type_name: &str) -> Option<String> {
+    let registry = ENUM_REGISTRY.lock().unwrap();
+    if registry.contains_key(type_name) {
+        return Some(type_name.to_string());
+    }
+
+    let relative_name = ["crate::", "self::"]
+        .iter()
+        .find_map(|prefix| type_name.strip_prefix(prefix))
+        .unwrap_or(type_name);
+    let suffix = format!("::{}", relative_name);
+    let mut matches = registry.keys().filter(|name| name.ends_with(&suffix));
+    match (matches.next(), matches.next()) {
+        (Some(name), None) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+/// Get the default option for a given enum type name
+///
+/// This is the numeric discriminant for `#[repr(u8)]` enums.
+#[wasm_bindgen]
+pub fn get_enum_default(type_name: &str) -> JsValue {
+    let Some(type_name) = registered_enum_name(type_name) else {
+        return JsValue::NULL;
+    };
+    let type_name = type_name.as_str();
+    let Some(default_option) = ENUM_DEFAULT_REGISTRY.lock().unwrap().get(type_name).copied() else {
+        return JsValue::NULL;
+    };
+
+    let discriminants = ENUM_DISCRIMINANT_REGISTRY.lock().unwrap();
+    let options = ENUM_REGISTRY.lock().unwrap();
+    let discriminant = discriminants.get(type_name).and_then(|discriminants| {
+        let index = options.get(type_name)?.iter().position(|option| option == default_option)?;
+        discriminants.get(index).copied()
+    });
+
+    match discriminant {
+        Some(discriminant) => JsValue::from_f64(discriminant as f64),
+        None => JsValue::from_str(default_option),
+    }
+}
+
+/// Get the select labels for a given enum type name, keyed by option value
+///
+/// For `#[repr(u8)]` enums this maps each discriminant to its variant name, and grouped
+/// options are labelled `group / option`. Other enums get an empty map.
+#[wasm_bindgen]
+pub fn get_enum_labels(type_name: &str) -> JsValue {
+    let type_name = registered_enum_name(type_name).unwrap_or_else(|| type_name.to_string());
+    let type_name = type_name.as_str();
+    let discriminants = ENUM_DISCRIMINANT_REGISTRY.lock().unwrap();
+    let options = ENUM_REGISTRY.lock().unwrap();
+    let groups = ENUM_GROUP_REGISTRY.lock().unwrap();
+    let groups = groups.get(type_name);
+
+    let label = |option: &String| match groups.and_then(|groups| groups.get(option)) {
+        Some(group_name) => format!("{} / {}", group_name, option),
+        None => option.clone(),
+    };
+    let labels: std::collections::HashMap<String, String> = match (discriminants.get(type_name), options.get(type_name)) {
+        (Some(discriminants), Some(options)) => discriminants
+            .iter()
+            .map(|discriminant| discriminant.to_string())
+            .zip(options.iter().map(label))
+            .collect(),
+        (None, Some(options)) if groups.is_some() => options
+            .iter()
+            .map(|option| (option.clone(), label(option)))
+            .collect(),
+        _ => std::collections::HashMap::new(),
+    };
+
+    labels
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap_or(JsValue::NULL)
+}
+
+/// Get the `options_const` select options of a story's field
+#[wasm_bindgen]
+pub fn get_const_options(story_name: &str, field_name: &str) -> JsValue {
+    match CONST_REGISTRY.lock().unwrap().get(&(story_name.to_string(), field_name.to_string())) {
+        Some(options) => serde_wasm_bindgen::to_value(options).unwrap_or(JsValue::NULL),
+        None => JsValue::NULL,
+    }
+}
+
+/// Serialize a `default_rust` value into the JS literal stored in `ArgType::default_value`
+#[doc(hidden)]
+pub fn __js_default<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "undefined".to_string())
+}
+
+/// Get the value of a story field's `default_rust` expression
+#[wasm_bindgen]
+pub fn get_rust_default(story_name: &str, field_name: &str) -> JsValue {
+    let Some(args_fn) = story_args_fn(story_name) else {
+        return JsValue::UNDEFINED;
+    };
+
+    args_fn()
+        .into_iter()
+        .find(|arg| arg.name == field_name)
+        .and_then(|arg| serde_json::from_str::<serde_json::Value>(arg.default_value.as_deref()?).ok())
+        .and_then(|value| value.serialize(&serde_wasm_bindgen::Serializer::json_compatible()).ok())
+        .unwrap_or(JsValue::UNDEFINED)
+}
+
+/// Get enum options for a given type name
+///
+/// These are the numeric discriminants for `#[repr(u8)]` enums, and the variant names otherwise.
+#[wasm_bindgen]
+pub fn get_enum_options(type_name: &str) -> JsValue {
+    let type_name = registered_enum_name(type_name).unwrap_or_else(|| type_name.to_string());
+    let type_name = type_name.as_str();
+    if let Some(discriminants) = ENUM_DISCRIMINANT_REGISTRY.lock().unwrap().get(type_name) {
+        return discriminants
+            .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+            .unwrap_or(JsValue::NULL);
+    }
+
+    let registry = ENUM_REGISTRY.lock().unwrap();
+    console_log(&format!("Getting enum options for {}, registry has {} entries", type_name, registry.len()));
+    if let Some(options) = registry.get(type_name) {
+        console_log(&format!("Found options: {:?}", options));
+        serde_wasm_bindgen::to_value(options).unwrap_or(JsValue::NULL)
+    } else {
+        console_log(&format!("No options found for {}", type_name));
+        JsValue::NULL
+    }
+}
+
+/// Check an invariant on a story's args inside `Story::to_story`
+///
+/// While Storybook renders the story, a failed invariant is reported by `get_story_errors`
+/// and shown below the story instead of panicking. Outside Storybook it acts like `debug_assert!`.
+///
+/// ```ignore
+/// story_invariant!(self.width > 0, "Width must be positive");
+/// ```
+#[macro_export]
+macro_rules! story_invariant {
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond && !$crate::__report_invariant(format!($($arg)+)) && cfg!(debug_assertions) {
+            panic!("story invariant failed: {}: {}", stringify!($cond), format!($($arg)+));
+        }
+    };
+}
+
+/// Record a failed invariant against the story being rendered
+///
+/// Returns `false` when no story is being rendered.
+#[doc(hidden)]
+pub fn __report_invariant(message: String) -> bool {
+    let Some(name) = CURRENT_STORY.with(|current| current.borrow().clone()) else {
+        return false;
+    };
+
+    STORY_ERRORS.lock().unwrap().entry(name).or_default().push(message);
+    true
+}
+
+/// Get the invariant violations reported by the last render of a story
+#[wasm_bindgen]
+pub fn get_story_errors(name: &str) -> JsValue {
+    let errors = STORY_ERRORS.lock().unwrap().get(name).cloned().unwrap_or_default();
+    serde_wasm_bindgen::to_value(&errors).unwrap_or(JsValue::NULL)
+}
+
+/// Macro to help register stories - used by derive macro
+#[macro_export]
+macro_rules! __register_story {
+    ($ty:ty) => {{
+        $crate::register_story($crate::StoryMeta {
+            name: <$ty as $crate::Story>::name(),
+            args: <$ty as $crate::Story>::args,
+            render_fn: <$ty as $crate::Story>::render,
+        });
+    }};
+}
+
+// Storybook's view of a story's args
+struct ArgsJson {
+    arg_types: serde_json::Map<String, serde_json::Value>,
+    default_args: serde_json::Map<String, serde_json::Value>,
+    depends_on: serde_json::Map<String, serde_json::Value>,
+}
+
+fn args_to_json(args: Vec<ArgType>) -> ArgsJson {
+    let mut arg_types = serde_json::Map::new();
+    let mut default_args = serde_json::Map::new();
+    let mut depends_on = serde_json::Map::new();
+
+    for arg in args {
+        if !arg.depends_on.is_empty() {
+            depends_on.insert(arg.name.clone(), serde_json::json!(arg.depends_on));
+        }
+
+        let mut arg_map = serde_json::Map::new();
+        let control = match arg.control {
+            // Switches are boolean controls whose values are mapped to on/off
+            ControlType::Switch => {
+                arg_map.insert(
+                    "mapping".to_string(),
+                    serde_json::json!({ "true": "on", "false": "off" }),
+                );
+                serde_json::json!({ "type": "boolean" })
+            }
+            // Options are labelled with their icons, in the same order
+            ControlType::InlineRadio if arg.radio_icons.is_some() => {
+                let labels: serde_json::Map<String, serde_json::Value> = arg
+                    .options
+                    .iter()
+                    .flatten()
+                    .zip(arg.radio_icons.iter().flatten())
+                    .map(|(option, icon)| (option.clone(), serde_json::Value::from(icon.as_str())))
+                    .collect();
+                serde_json::json!({ "type": "inline-radio", "labels": labels })
+            }
+            // Control components registered in preview.js are passed through by name
+            ControlType::Custom if arg.custom_control.is_some() => {
+                serde_json::json!({ "type": "custom", "component": arg.custom_control })
+            }
+            // Comboboxes are text inputs suggesting the options, rendered by an addon reading `allowCustomValue`
+            ControlType::Select if arg.allow_custom_value => {
+                arg_map.insert("options".to_string(), serde_json::json!(arg.options));
+                arg_map.insert("allowCustomValue".to_string(), serde_json::Value::Bool(true));
+                serde_json::json!({ "type": "text" })
+            }
+            ControlType::Text if arg.min_length.is_some() => {
+                serde_json::json!({ "type": "text", "minLength": arg.min_length })
+            }
+            // Percentages are edited as 0-100 and divided by 100 before rendering
+            ControlType::Number | ControlType::Range if arg.is_percentage() => {
+                arg_map.insert("displayFormat".to_string(), serde_json::Value::from("percentage"));
+                serde_json::json!({ "type": arg.control, "min": 0, "max": 100, "step": 1 })
+            }
+            _ => serde_json::to_value(&arg.control).unwrap(),
+        };
+
+        let mut table = serde_json::Map::new();
+        if arg.required {
+            table.insert("category".to_string(), serde_json::Value::from("required"));
+        } else {
+            table.insert("category".to_string(), serde_json::Value::from("optional"));
+        }
+        if let Some(type_detail) = arg.type_detail() {
+            table.insert("type".to_string(), serde_json::json!({ "detail": type_detail }));
+        }
+
+        arg_map.insert("name".to_string(), serde_json::Value::String(arg.name.clone()));
+        arg_map.insert("control".to_string(), control);
+        if let Some(description) = &arg.description {
+            arg_map.insert("description".to_string(), serde_json::Value::from(description.as_str()));
+        }
+        arg_map.insert("table".to_string(), serde_json::Value::Object(table));
+        if arg.textarea_rows.is_some() || arg.textarea_resize.is_some() {
+            let mut component_props = serde_json::Map::new();
+            if let Some(rows) = arg.textarea_rows {
+                component_props.insert("rows".to_string(), serde_json::Value::from(rows));
+            }
+            if let Some(resize) = arg.textarea_resize {
+                component_props.insert("style".to_string(), serde_json::Value::from(format!("resize: {}", resize)));
+            }
+            arg_map.insert("componentProps".to_string(), serde_json::Value::Object(component_props));
+        }
+        if let Some(option_groups) = &arg.option_groups {
+            arg_map.insert("optionGroups".to_string(), serde_json::json!(option_groups));
+        }
+        if let Some((field, ratio)) = &arg.linked_field {
+            arg_map.insert("linkedTo".to_string(), serde_json::json!({ "field": field, "ratio": ratio }));
+        }
+        if let Some(number_format) = &arg.number_format {
+            arg_map.insert(
+                "numberFormat".to_string(),
+                serde_json::json!({ "style": number_format, "currency": arg.number_currency }),
+            );
+        }
+
+        if let Some(default) = &arg.default_value {
+            let default = match default.parse::<f64>() {
+                Ok(number) if arg.is_percentage() => arg.to_display_value(serde_json::json!(number)).to_string(),
+                _ => default.clone(),
+            };
+            default_args.insert(arg.name.clone(), serde_json::Value::String(default));
+        }
+
+        arg_types.insert(arg.name, serde_json::Value::Object(arg_map));
+    }
+
+    ArgsJson { arg_types, default_args, depends_on }
+}
+
+/// Get all registered stories as Storybook-compatible format
+#[wasm_bindgen]
+pub fn get_stories() -> JsValue {
+    // Copied out first, as the args functions are called below and may look up stories themselves
+    let stories = story_registrations();
+    let story_defs: Vec<_> = stories
+        .iter()
+        .map(|meta| {
+            let ArgsJson { mut arg_types, default_args, depends_on } = args_to_json((meta.args)());
+            for arg_type in arg_types.values_mut() {
+                if let serde_json::Value::Object(arg_type) = arg_type {
+                    meta.hide_arg_table_columns(arg_type);
+                }
+            }
+
+            let mut parameters = serde_json::Map::new();
+            if meta.canvas_width.is_some() || meta.canvas_height.is_some() {
+                let mut canvas_layout = serde_json::Map::new();
+                if let Some(width) = meta.canvas_width {
+                    canvas_layout.insert("width".to_string(), serde_json::Value::from(width));
+                }
+                if let Some(height) = meta.canvas_height {
+                    canvas_layout.insert("height".to_string(), serde_json::Value::from(height));
+                }
+                parameters.insert("layout".to_string(), serde_json::Value::from("padded"));
+                parameters.insert("canvasLayout".to_string(), serde_json::Value::Object(canvas_layout));
+            }
+
+            if let Some(padding) = meta.canvas_padding {
+                parameters.insert("layout".to_string(), serde_json::Value::from("padded"));
+                parameters.insert("canvasPadding".to_string(), serde_json::Value::from(padding));
+            }
+
+            if let Some(note) = meta.note {
+                parameters.insert("notes".to_string(), serde_json::Value::from(note));
+            }
+
+            let mut controls = serde_json::Map::new();
+            if !depends_on.is_empty() {
+                controls.insert("dependsOn".to_string(), serde_json::json!(depends_on));
+            }
+            if let Some(expanded) = meta.controls_expanded_param() {
+                controls.insert("expanded".to_string(), serde_json::Value::Bool(expanded));
+            }
+            if !controls.is_empty() {
+                parameters.insert("controls".to_string(), serde_json::Value::Object(controls));
+            }
+
+            if !meta.keyboard_shortcuts.is_empty() {
+                let shortcuts: serde_json::Map<String, serde_json::Value> = meta
+                    .keyboard_shortcuts
+                    .iter()
+                    .map(|(key, action)| (key.to_string(), serde_json::Value::from(*action)))
+                    .collect();
+                parameters.insert("keyboard".to_string(), serde_json::Value::Object(shortcuts));
+            }
+
+            if !meta.feature_flags.is_empty() {
+                parameters.insert("featureFlags".to_string(), serde_json::json!(meta.feature_flags));
+            }
+
+            let mut docs = serde_json::Map::new();
+            if let Some(component_docs) = meta.component_docs {
+                docs.insert("description".to_string(), serde_json::json!({ "component": component_docs }));
+            }
+            if !docs.is_empty() {
+                parameters.insert("docs".to_string(), serde_json::Value::Object(docs));
+            }
+
+            serde_json::json!({
+                "name": meta.name,
+                "id": meta.component_id,
+                "primaryVariantName": meta.primary_variant_name,
+                "argTypes": arg_types,
+                "args": default_args,
+                "interactDelayMs": meta.interact_delay_ms,
+                "ariaLabel": meta.aria_label,
+                "featureFlags": meta.feature_flags,
+                "decorators": meta.decorators,
+                "dependencies": meta.dependencies,
+                "composedOf": meta
+                    .composed_stories
+                    .iter()
+                    .map(|composed| match composed.variant {
+                        Some(variant) => format!("{}::{}", composed.story, variant),
+                        None => composed.story.to_string(),
+                    })
+                    .collect::<Vec<_>>(),
+                "screenshot": meta.screenshot,
+                "defaultViewport": meta.default_viewport,
+                "portalTarget": meta.portal_target,
+                "sourceLocation": meta.source_location,
+                "parameters": parameters,
+            })
+        })
         .collect();
 
     serde_wasm_bindgen::to_value(&story_defs).unwrap()
 }
 
+/// Get the `argTypes` and default `args` of one of a story's variants
+///
+/// Variants without their own arg types, including the main one, get the story's args.
+#[wasm_bindgen]
+pub fn get_story_args_for_variant(name: &str, variant: &str) -> JsValue {
+    let Some(args_fn) = with_story(name, |meta| match meta.variant_args.get(variant) {
+        Some(&variant_args) => Arc::new(variant_args) as ArgsFn,
+        None => meta.args.clone(),
+    }) else {
+        return JsValue::NULL;
+    };
+
+    let ArgsJson { arg_types, default_args, .. } = args_to_json(args_fn());
+    serde_wasm_bindgen::to_value(&serde_json::json!({
+        "argTypes": arg_types,
+        "args": default_args,
+    }))
+    .unwrap()
+}
+
+/// Get a story's variants other than the main one as `[{ name, args }]`, ordered by name
+///
+/// Used by the `AutoPlay` story of `#[story(auto_play = "...")]` to cycle through them.
+#[wasm_bindgen]
+pub fn get_story_variants(name: &str) -> JsValue {
+    let Some(mut variants) =
+        with_story(name, |meta| meta.variant_args.iter().map(|(variant, args_fn)| (*variant, *args_fn)).collect::<Vec<_>>())
+    else {
+        return JsValue::NULL;
+    };
+    variants.sort_by_key(|(variant, _)| *variant);
+
+    let variants: Vec<serde_json::Value> = variants
+        .into_iter()
+        .map(|(variant, args_fn)| {
+            let ArgsJson { default_args, .. } = args_to_json(args_fn());
+            serde_json::json!({ "name": variant, "args": default_args })
+        })
+        .collect();
+    serde_wasm_bindgen::to_value(&variants).unwrap()
+}
+
+/// Render the inline control preview for one of a story's args
+#[wasm_bindgen]
+pub fn render_inline_preview(name: &str, arg: &str, value: JsValue) -> Result<web_sys::Element, JsValue> {
+    let preview_fn = with_story(name, |meta| meta.inline_previews.get(arg).copied())
+        .ok_or_else(|| JsValue::from_str(&format!("Story '{}' not found", name)))?
+        .ok_or_else(|| JsValue::from_str(&format!("Story '{}' has no inline preview for '{}'", name, arg)))?;
+
+    Ok(preview_fn(value))
+}
+
+/// Get the select options of a dependent arg for the story's current args
+#[wasm_bindgen]
+pub fn get_dependent_options(name: &str, field: &str, context: JsValue) -> Result<JsValue, JsValue> {
+    let options_fn = with_story(name, |meta| meta.dependent_options.get(field).copied())
+        .ok_or_else(|| JsValue::from_str(&format!("Story '{}' not found", name)))?
+        .ok_or_else(|| JsValue::from_str(&format!("Story '{}' has no dependent options for '{}'", name, field)))?;
+
+    Ok(serde_wasm_bindgen::to_value(&options_fn(context))?)
+}
+
+/// Get the translation keys of a story's args, as a map of field name to i18n key
+#[wasm_bindgen]
+pub fn get_story_i18n_keys(name: &str) -> JsValue {
+    let Some(args) = story_args_fn(name) else {
+        return JsValue::NULL;
+    };
+
+    let i18n_keys: std::collections::HashMap<String, String> = args()
+        .into_iter()
+        .filter_map(|arg| Some((arg.name, arg.i18n_key?)))
+        .collect();
+
+    i18n_keys
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap_or(JsValue::NULL)
+}
+
+/// Get the names of the stories a story depends on, from `#[story(depends_on = "...")]`
+#[wasm_bindgen]
+pub fn get_story_dependencies(name: &str) -> JsValue {
+    let stories = STORY_REGISTRY.lock().unwrap();
+    match stories.iter().find(|meta| meta.name == name) {
+        Some(meta) => serde_wasm_bindgen::to_value(meta.dependencies).unwrap_or(JsValue::NULL),
+        None => JsValue::NULL,
+    }
+}
+
+/// Get the dependencies of every registered story, as a map of story name to the names it depends on
+#[wasm_bindgen]
+pub fn get_story_dependency_graph() -> JsValue {
+    let stories = STORY_REGISTRY.lock().unwrap();
+    let graph: std::collections::HashMap<&str, &[&str]> = stories
+        .iter()
+        .map(|meta| (meta.name, meta.dependencies))
+        .collect();
+
+    graph
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap_or(JsValue::NULL)
+}
+
 /// Render a story by name with the given arguments
 /// Returns the DOM node for the story
 #[wasm_bindgen]
 pub fn render_story(name: &str, args: JsValue) -> Result<web_sys::Node, JsValue> {
-    let story_dom = STORY_REGISTRY
-        .lock()
-        .unwrap()
-        .iter()
-        .find(|meta| meta.name == name)
-        .map(|meta| (meta.render_fn)(args.clone()))
+    let container = render_story_container(name, args)?;
+
+    // Return the container as a Node
+    Ok(container.into())
+}
+
+/// Render a story by name inside a theme context
+///
+/// `theme` maps CSS custom property names (e.g. `--primary-color`) to values, which
+/// are set on the story's container so Storybook's theme toolbar can restyle it.
+#[wasm_bindgen]
+pub fn render_story_with_theme(name: &str, args: JsValue, theme: JsValue) -> Result<web_sys::Node, JsValue> {
+    let theme: std::collections::HashMap<String, String> = if theme.is_null() || theme.is_undefined() {
+        std::collections::HashMap::new()
+    } else {
+        serde_wasm_bindgen::from_value(theme)?
+    };
+
+    let container = render_story_container(name, args)?;
+    let style = container
+        .dyn_ref::<web_sys::HtmlElement>()
+        .ok_or_else(|| JsValue::from_str("Story container is not an HTML element"))?
+        .style();
+
+    for (property, value) in &theme {
+        style.set_property(property, value)?;
+    }
+
+    Ok(container.into())
+}
+
+/// Update a rendered story with live controls from new args, without re-rendering it
+///
+/// Returns `false` if the story has no live instance to update, in which case it should be rendered.
+#[wasm_bindgen]
+pub fn update_story_arg(name: &str, args: JsValue) -> Result<bool, JsValue> {
+    let update_fn = with_story(name, |meta| meta.update_fn)
         .ok_or_else(|| JsValue::from_str(&format!("Story '{}' not found", name)))?;
-    
-    // Create a container element
+    let Some(update_fn) = update_fn else {
+        return Ok(false);
+    };
+
+    STORY_INSTANCES.with(|instances| match instances.borrow().get(name) {
+        Some(handles) => update_fn(handles.as_ref(), args).map(|_| true),
+        None => Ok(false),
+    })
+}
+
+/// Deserialize new args for a story and pass them to `apply` with its live handles
+#[doc(hidden)]
+pub fn __update_live<T, H: 'static>(handles: &dyn std::any::Any, args: JsValue, apply: fn(&H, T)) -> Result<(), JsValue>
+where
+    T: Story + StoryMeta,
+{
+    let handles = handles
+        .downcast_ref::<H>()
+        .ok_or_else(|| JsValue::from_str(&format!("Story '{}' has no live handles", T::name())))?;
+    let component: T::StoryArgs = serde_wasm_bindgen::from_value(args)?;
+    apply(handles, component.into());
+    Ok(())
+}
+
+/// Render a story by name and mount it into an existing element, e.g. a portal target
+#[wasm_bindgen]
+pub fn mount_story(name: &str, args: JsValue, container: &web_sys::Element) -> Result<(), JsValue> {
+    mount_story_into(name, args, container)
+}
+
+/// Render a story type into a new container element, bypassing the registry
+///
+/// Used by the exports generated for `#[story(wasm_export_name = "...")]`.
+pub fn render_story_direct<T: Story + StoryMeta>(args: JsValue) -> Result<web_sys::Node, JsValue> {
     let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
     let document = window.document().ok_or_else(|| JsValue::from_str("No document"))?;
     let container = document.create_element("div")?;
-    
-    // Append the story DOM to the container
-    dominator::append_dom(&container, story_dom);
-    
-    // Return the container as a Node
+
+    T::render_to_dom(args, &container);
+
     Ok(container.into())
 }
 
+// Render a story into a new container element
+pub(crate) fn render_story_container(name: &str, args: JsValue) -> Result<web_sys::Element, JsValue> {
+    // Portals are outside the container Storybook clears, so a modal would stay over the next story
+    clear_portal_mounts()?;
+
+    if let Some(container) = RENDERED_ONCE.with(|rendered| rendered.borrow().get(name).cloned()) {
+        return Ok(container);
+    }
+
+    let portal_target = with_story(name, |meta| meta.portal_target).flatten();
+    if let Some(portal_target) = portal_target {
+        return render_into_portal(name, args, portal_target);
+    }
+
+    // Create a container element
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
+    let document = window.document().ok_or_else(|| JsValue::from_str("No document"))?;
+    let container = document.create_element("div")?;
+
+    mount_story_into(name, args, &container)?;
+
+    let render_once = with_story(name, |meta| meta.render_once).unwrap_or(false);
+    if render_once {
+        RENDERED_ONCE.with(|rendered| rendered.borrow_mut().insert(name.to_string(), container.clone()));
+    }
+
+    Ok(container)
+}
+
+/// Render a story with a `portal_target` into that element, returning the portal element
+///
+/// The element is created at the end of `<body>` if it doesn't exist. The story gets a mount point
+/// there, removed when the next story renders so it doesn't stay on screen.
+#[wasm_bindgen]
+pub fn render_story_with_portal(name: &str, args: JsValue) -> Result<web_sys::Node, JsValue> {
+    let portal_target = with_story(name, |meta| meta.portal_target)
+        .ok_or_else(|| JsValue::from_str(&format!("Story '{}' not found", name)))?
+        .ok_or_else(|| JsValue::from_str(&format!("Story '{}' has no portal target", name)))?;
+
+    Ok(render_into_portal(name, args, portal_target)?.into())
+}
+
+fn render_into_portal(name: &str, args: JsValue, portal_target: &str) -> Result<web_sys::Element, JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
+    let document = window.document().ok_or_else(|| JsValue::from_str("No document"))?;
+    // Looked up by ID rather than selector, as IDs like `#1modal` aren't valid selectors
+    let portal_id = portal_target.trim_start_matches('#');
+    let portal = match document.get_element_by_id(portal_id) {
+        Some(portal) => portal,
+        None => {
+            let portal = document.create_element("div")?;
+            portal.set_id(portal_id);
+            document.body().ok_or_else(|| JsValue::from_str("No body"))?.append_child(&portal)?;
+            portal
+        }
+    };
+
+    clear_portal_mounts()?;
+    let mount_point = document.create_element("div")?;
+    mount_point.set_attribute("data-story-portal", name)?;
+    portal.append_child(&mount_point)?;
+
+    mount_story_into(name, args, &mount_point)?;
+    Ok(portal)
+}
+
+// Remove the mount points of stories rendered into portals, before the next story renders
+fn clear_portal_mounts() -> Result<(), JsValue> {
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return Ok(());
+    };
+    while let Some(mount_point) = document.query_selector("[data-story-portal]")? {
+        mount_point.remove();
+    }
+    Ok(())
+}
+
+/// Forget the mounted container of a `#[story(once)]` story, so it renders again next time
+#[wasm_bindgen]
+pub fn reset_story_instance(name: &str) {
+    RENDERED_ONCE.with(|rendered| rendered.borrow_mut().remove(name));
+}
+
+// Render a story and append it to `container`
+fn mount_story_into(name: &str, args: JsValue, container: &web_sys::Element) -> Result<(), JsValue> {
+    let (render_to_dom_fn, error_boundary, track_renders, canvas_padding, arg_types) = with_story(name, |meta| {
+        (meta.render_to_dom_fn.clone(), meta.error_boundary, meta.track_renders, meta.canvas_padding, meta.args.clone())
+    })
+    .ok_or_else(|| JsValue::from_str(&format!("Story '{}' not found", name)))?;
+
+    controls::validate_args(name, &arg_types(), &args)?;
+
+    if let (Some(padding), Some(container)) = (canvas_padding, container.dyn_ref::<web_sys::HtmlElement>()) {
+        container.style().set_property("padding", padding)?;
+    }
+
+    // The registry lock is released before rendering, so a panic can't poison it
+    let story_guard = CurrentStoryGuard::enter(name);
+    if error_boundary {
+        let rendered = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| render_to_dom_fn(args, container)));
+        if let Err(panic) = rendered {
+            let error = render_error(&format!("Story '{}' failed to render", name), &panic_message(panic.as_ref()));
+            dominator::append_dom(container, error);
+        }
+    } else {
+        render_to_dom_fn(args, container);
+    }
+    drop(story_guard);
+
+    // Show failed invariants below the story
+    if let Some(errors) = STORY_ERRORS.lock().unwrap().get(name) {
+        let overlay = render_error(&format!("Story '{}' broke its invariants", name), &errors.join("\n"));
+        dominator::append_dom(container, overlay);
+    }
+
+    if track_renders {
+        let count = {
+            let mut counts = RENDER_COUNTS.lock().unwrap();
+            let count = counts.entry(name.to_string()).or_insert(0);
+            *count += 1;
+            *count
+        };
+
+        // The counter is positioned against the container's corner
+        if let Some(container) = container.dyn_ref::<web_sys::HtmlElement>() {
+            container.style().set_property("position", "relative")?;
+        }
+        dominator::append_dom(container, render_count_overlay(count));
+    }
+
+    Ok(())
+}
+
+// Small badge in the corner of a story showing how often it has rendered
+fn render_count_overlay(count: u32) -> Dom {
+    html!("div", {
+        .attr("title", "Render count")
+        .style("position", "absolute")
+        .style("top", "2px")
+        .style("right", "2px")
+        .style("padding", "0 4px")
+        .style("font-size", "10px")
+        .style("font-family", "monospace")
+        .style("background", "red")
+        .style("color", "white")
+        .style("pointer-events", "none")
+        .text(&count.to_string())
+    })
+}
+
+// Get the message out of a panic payload, which is a `&str` or `String` for `panic!` calls
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+// Render an error shown in a story's container, e.g. when it panicked
+fn render_error(title: &str, message: &str) -> Dom {
+    html!("div", {
+        .attr("role", "alert")
+        .style("padding", "15px 20px")
+        .style("border", "1px solid #e74c3c")
+        .style("border-radius", "4px")
+        .style("background-color", "#fdecea")
+        .style("color", "#c0392b")
+        .style("font-family", "monospace")
+        .style("white-space", "pre-wrap")
+        .text(&format!("{}:\n{}", title, message))
+    })
+}
+
 /// Export stories in Storybook CSF (Component Story Format) compatible format
 #[wasm_bindgen]
 pub fn export_stories_csf() -> JsValue {
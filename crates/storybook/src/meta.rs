@@ -0,0 +1,146 @@
+use dominator::Dom;
+use std::collections::HashMap;
+use std::sync::Arc;
+use wasm_bindgen::JsValue;
+
+use crate::{ArgType, ArgsFn, RenderToDomFn, StoryRegistration};
+
+/// Function rendering a story configured at runtime from its args
+pub type DynamicRenderFn = Arc<dyn Fn(JsValue) -> Dom + Send + Sync>;
+
+/// Story configured at runtime instead of with `#[derive(Story)]`, e.g. for plugins or stories built from data
+///
+/// The story is registered like a derived one, so `render_story`, `get_stories` and
+/// `storybook_codegen::generate_all_story_files` work with it, but no `.stories.js` file is
+/// written while compiling. The render function gets the args as JS and must be `Send + Sync`
+/// to be kept in the registry.
+///
+/// ```ignore
+/// StoryMetaBuilder::new("DynamicButton")
+///     .arg(ArgTypeBuilder::new("label").control(ControlType::Text).default("'Click'").build()?)
+///     .render(|args| render_button_from_json(args))
+///     .register()?;
+/// ```
+pub struct StoryMetaBuilder {
+    name: String,
+    args: Vec<ArgType>,
+    render: Option<DynamicRenderFn>,
+    description: Option<String>,
+    error_boundary: bool,
+}
+
+impl StoryMetaBuilder {
+    /// Start a story with the given name and no args
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            args: Vec::new(),
+            render: None,
+            description: None,
+            error_boundary: false,
+        }
+    }
+
+    /// Add an arg, in the order they're shown in the controls panel
+    pub fn arg(mut self, arg: ArgType) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    /// Set the function rendering the story from its args
+    pub fn render(mut self, render: impl Fn(JsValue) -> Dom + Send + Sync + 'static) -> Self {
+        self.render = Some(Arc::new(render));
+        self
+    }
+
+    /// Set the component description shown in the docs page
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Show a panic while rendering as an error in the story instead of aborting, like `#[story(error_boundary)]`
+    pub fn error_boundary(mut self, error_boundary: bool) -> Self {
+        self.error_boundary = error_boundary;
+        self
+    }
+
+    /// Add the story to the registry
+    pub fn register(self) -> Result<(), StoryMetaError> {
+        // Story names are exported as is from `.stories.js` files, so they must be valid there
+        if !crate::template::is_js_identifier(&self.name) {
+            return Err(StoryMetaError::InvalidName(self.name));
+        }
+        let render = self.render.ok_or_else(|| StoryMetaError::MissingRender(self.name.clone()))?;
+
+        let args = self.args;
+        let args_fn: ArgsFn = Arc::new(move || args.clone());
+        let render_to_dom_fn: RenderToDomFn = Arc::new(move |args: JsValue, container: &web_sys::Element| {
+            dominator::append_dom(container, render(args));
+        });
+
+        // Registrations live as long as the program, so their strings are leaked once here
+        let name: &'static str = Box::leak(self.name.into_boxed_str());
+        let registration = StoryRegistration {
+            name,
+            title: format!("Components/{}", name),
+            args: args_fn,
+            variant_args: HashMap::new(),
+            render_to_dom_fn,
+            canvas_width: None,
+            canvas_height: None,
+            canvas_padding: None,
+            default_viewport: None,
+            component_id: None,
+            primary_variant_name: "Default",
+            interact_delay_ms: None,
+            component_docs: self.description.map(|description| &*Box::leak(description.into_boxed_str())),
+            note: None,
+            source_location: None,
+            aria_label: None,
+            error_boundary: self.error_boundary,
+            track_renders: false,
+            render_once: false,
+            portal_target: None,
+            screenshot: false,
+            arg_table_columns: &[],
+            controls_expanded: None,
+            feature_flags: &[],
+            decorators: &[],
+            dependencies: &[],
+            composed_stories: &[],
+            story_template: || None,
+            keyboard_shortcuts: &[],
+            inline_previews: HashMap::new(),
+            dependent_options: HashMap::new(),
+            update_fn: None,
+        };
+        if !crate::add_story(registration) {
+            return Err(StoryMetaError::AlreadyRegistered(name.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Why a `StoryMetaBuilder` couldn't register its story
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoryMetaError {
+    /// No render function was set
+    MissingRender(String),
+    /// A story with the same name is already registered
+    AlreadyRegistered(String),
+    /// The name isn't a JavaScript identifier, which story names are exported as
+    InvalidName(String),
+}
+
+impl std::fmt::Display for StoryMetaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoryMetaError::MissingRender(name) => write!(f, "story '{}' has no render function", name),
+            StoryMetaError::AlreadyRegistered(name) => write!(f, "story '{}' is already registered", name),
+            StoryMetaError::InvalidName(name) => write!(f, "story name '{}' is not a JavaScript identifier", name),
+        }
+    }
+}
+
+impl std::error::Error for StoryMetaError {}
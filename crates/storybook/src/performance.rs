@@ -0,0 +1,37 @@
+use wasm_bindgen::prelude::*;
+
+// Names of the marks and measure the `.stories.js` template records around a
+// `#[story(measure_performance)]` story's render
+fn mark_names(story_name: &str) -> [String; 2] {
+    [
+        format!("storybook-render-start-{}", story_name),
+        format!("storybook-render-end-{}", story_name),
+    ]
+}
+
+fn measure_name(story_name: &str) -> String {
+    format!("storybook-render-{}", story_name)
+}
+
+fn performance() -> Result<web_sys::Performance, JsValue> {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .ok_or_else(|| JsValue::from_str("Performance API not available"))
+}
+
+/// Get the render timings of a story with `measure_performance`, as `PerformanceMeasure` entries
+#[wasm_bindgen]
+pub fn get_performance_entries(story_name: &str) -> Result<JsValue, JsValue> {
+    Ok(performance()?.get_entries_by_name(&measure_name(story_name)).into())
+}
+
+/// Forget the render timings recorded for a story
+#[wasm_bindgen]
+pub fn clear_performance_marks(story_name: &str) -> Result<(), JsValue> {
+    let performance = performance()?;
+    for mark_name in mark_names(story_name) {
+        performance.clear_marks_with_mark_name(&mark_name);
+    }
+    performance.clear_measures_with_measure_name(&measure_name(story_name));
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use crate::ControlType;
+
+/// A global arg shown in Storybook's toolbar and passed to every story, e.g. theme or locale
+///
+/// ```ignore
+/// GlobalArg::new("theme", ControlType::Select, "light")
+///     .description("Color theme")
+///     .options(&["light", "dark"])
+///     .declare();
+/// ```
+#[derive(Debug, Clone)]
+pub struct GlobalArg {
+    pub name: &'static str,
+    pub description: Option<&'static str>,
+    pub control: ControlType,
+    pub default_value: &'static str,
+    pub options: Vec<&'static str>,
+}
+
+impl GlobalArg {
+    /// Create a global arg with the given control and default value
+    pub fn new(name: &'static str, control: ControlType, default_value: &'static str) -> Self {
+        Self {
+            name,
+            description: None,
+            control,
+            default_value,
+            options: Vec::new(),
+        }
+    }
+
+    /// Set the tooltip shown for the arg in the toolbar
+    pub fn description(mut self, description: &'static str) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    /// Set the values offered in the toolbar dropdown
+    pub fn options(mut self, options: &[&'static str]) -> Self {
+        self.options = options.to_vec();
+        self
+    }
+
+    /// Register the global arg, replacing any earlier declaration with the same name
+    pub fn declare(self) {
+        let mut registry = GLOBAL_ARG_REGISTRY.lock().unwrap();
+        registry.retain(|arg| arg.name != self.name);
+        registry.push(self);
+    }
+}
+
+// Global registry for declared global args
+static GLOBAL_ARG_REGISTRY: Lazy<Mutex<Vec<GlobalArg>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Project-wide Storybook settings written out as a `preview.js` module
+///
+/// ```ignore
+/// StoryConfig::new()
+///     .addon_backgrounds(&[("white", "#fff"), ("dark", "#1a1a1a"), ("brand", "#007bff")])
+///     .write_preview(".storybook/generated-preview.js")?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StoryConfig {
+    pub backgrounds: Vec<(String, String)>,
+}
+
+impl StoryConfig {
+    /// Start an empty configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the backgrounds addon's colors as `(name, color)` pairs; the first one is the default
+    pub fn addon_backgrounds(mut self, colors: &[(&str, &str)]) -> Self {
+        self.backgrounds = colors
+            .iter()
+            .map(|(name, color)| (name.to_string(), color.to_string()))
+            .collect();
+        self
+    }
+
+    /// Render the configuration as a module exporting `parameters` and `initialGlobals`
+    pub fn to_preview_js(&self) -> String {
+        let mut parameters = Vec::new();
+        let mut initial_globals = Vec::new();
+
+        if !self.backgrounds.is_empty() {
+            let options: Vec<String> = self
+                .backgrounds
+                .iter()
+                .map(|(name, color)| {
+                    format!(
+                        "      {}: {{ name: {}, value: {} }}",
+                        js_string(name),
+                        js_string(name),
+                        js_string(color)
+                    )
+                })
+                .collect();
+            parameters.push(format!("  backgrounds: {{\n    options: {{\n{}\n    }},\n  }},", options.join(",\n")));
+            initial_globals.push(format!("  backgrounds: {{ value: {} }},", js_string(&self.backgrounds[0].0)));
+        }
+
+        format!(
+            "// Generated by storybook::preview::StoryConfig - do not edit\n\nexport const parameters = {};\n\nexport const initialGlobals = {};\n",
+            js_object(&parameters),
+            js_object(&initial_globals)
+        )
+    }
+
+    /// Write the configuration to `path`, e.g. from a build script
+    pub fn write_preview(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_preview_js())
+    }
+}
+
+// Wrap already indented `key: value,` lines in braces
+fn js_object(entries: &[String]) -> String {
+    if entries.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{\n{}\n}}", entries.join("\n"))
+    }
+}
+
+// JSON strings are valid JS string literals
+fn js_string(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// Declare a global arg with no description or toolbar options
+pub fn declare_global_arg(name: &'static str, control: ControlType, default_value: &'static str) {
+    GlobalArg::new(name, control, default_value).declare();
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GlobalTypeToolbar {
+    title: &'static str,
+    items: Vec<&'static str>,
+    dynamic_title: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GlobalType {
+    name: &'static str,
+    description: Option<&'static str>,
+    default_value: &'static str,
+    control: ControlType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    toolbar: Option<GlobalTypeToolbar>,
+}
+
+/// Get the declared global args as Storybook `globalTypes`, for use in `preview.js`
+#[wasm_bindgen]
+pub fn get_global_types() -> JsValue {
+    let registry = GLOBAL_ARG_REGISTRY.lock().unwrap();
+    let global_types: std::collections::HashMap<&str, GlobalType> = registry
+        .iter()
+        .map(|arg| {
+            let toolbar = if arg.options.is_empty() {
+                None
+            } else {
+                Some(GlobalTypeToolbar {
+                    title: arg.name,
+                    items: arg.options.clone(),
+                    dynamic_title: true,
+                })
+            };
+
+            let global_type = GlobalType {
+                name: arg.name,
+                description: arg.description,
+                default_value: arg.default_value,
+                control: arg.control.clone(),
+                toolbar,
+            };
+            (arg.name, global_type)
+        })
+        .collect();
+
+    global_types
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap_or(JsValue::NULL)
+}
+
+/// Render a story with Storybook's current global args
+///
+/// Declared globals are passed to the story as args of the same name, unless the
+/// story sets that arg itself.
+#[wasm_bindgen]
+pub fn render_story_with_globals(name: &str, args: JsValue, globals: JsValue) -> Result<web_sys::Node, JsValue> {
+    let mut merged: serde_json::Map<String, serde_json::Value> = if args.is_null() || args.is_undefined() {
+        serde_json::Map::new()
+    } else {
+        serde_wasm_bindgen::from_value(args)?
+    };
+    let globals: serde_json::Map<String, serde_json::Value> = if globals.is_null() || globals.is_undefined() {
+        serde_json::Map::new()
+    } else {
+        serde_wasm_bindgen::from_value(globals)?
+    };
+
+    for arg in GLOBAL_ARG_REGISTRY.lock().unwrap().iter() {
+        let value = globals
+            .get(arg.name)
+            .cloned()
+            .unwrap_or_else(|| serde_json::Value::from(arg.default_value));
+        merged.entry(arg.name.to_string()).or_insert(value);
+    }
+
+    let args = merged.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?;
+    let container = crate::render_story_container(name, args)?;
+    Ok(container.into())
+}
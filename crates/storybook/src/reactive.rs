@@ -0,0 +1,97 @@
+use dominator::Dom;
+use std::sync::{Arc, Mutex};
+use wasm_bindgen::JsValue;
+
+pub use futures_signals::signal::Mutable;
+
+/// Story whose fields can be held in `Mutable`s, implemented by `#[story(reactive)]`
+pub trait Reactive: Sized + 'static {
+    /// The story's fields, each wrapped in a `Mutable`
+    type Fields: Clone + 'static;
+
+    fn to_fields(&self) -> Self::Fields;
+
+    /// Set each field's `Mutable` to this story's value, leaving unchanged fields alone
+    fn set_fields(&self, fields: &Self::Fields);
+}
+
+/// Story args that update in place, so arg changes only re-render the DOM bound to them
+///
+/// `get()` and `set()` work with the whole story, while `fields()` holds a `Mutable` per field
+/// whose signals drive the DOM. `set()` only touches the fields that changed, so just the
+/// subtrees built from their signals re-render.
+pub struct ReactiveStoryArgs<T: Reactive> {
+    args: Arc<Mutex<T>>,
+    fields: T::Fields,
+}
+
+impl<T: Reactive> Clone for ReactiveStoryArgs<T> {
+    fn clone(&self) -> Self {
+        Self {
+            args: self.args.clone(),
+            fields: self.fields.clone(),
+        }
+    }
+}
+
+impl<T: Reactive + Clone> ReactiveStoryArgs<T> {
+    pub fn new(args: T) -> Self {
+        let fields = args.to_fields();
+        Self {
+            args: Arc::new(Mutex::new(args)),
+            fields,
+        }
+    }
+
+    /// The current args
+    pub fn get(&self) -> T {
+        self.args.lock().unwrap().clone()
+    }
+
+    /// Replace the args, updating the `Mutable` of each field that changed
+    pub fn set(&self, args: T) {
+        args.set_fields(&self.fields);
+        *self.args.lock().unwrap() = args;
+    }
+
+    /// A `Mutable` per field, to build signals from
+    pub fn fields(&self) -> &T::Fields {
+        &self.fields
+    }
+}
+
+/// Story rendered from reactive args, for `#[story(reactive)]`
+///
+/// Storybook arg changes are passed to `set()` on the rendered story's args instead of
+/// re-rendering it, so the DOM and component state outside the changed fields are kept.
+/// The story and its fields need `Clone`, and the fields `PartialEq` to tell which changed.
+///
+/// ```ignore
+/// #[derive(Story, Deserialize, Clone)]
+/// #[story(reactive)]
+/// struct Counter {
+///     label: String,
+///     count: i32,
+/// }
+///
+/// impl ReactiveStory for Counter {
+///     fn to_reactive_story(args: ReactiveStoryArgs<Self>) -> Dom {
+///         html!("button", {
+///             .text_signal(args.fields().label.signal_cloned())
+///         })
+///     }
+/// }
+/// ```
+pub trait ReactiveStory: Reactive + Clone {
+    fn to_reactive_story(args: ReactiveStoryArgs<Self>) -> Dom;
+}
+
+/// Render a reactive story, keeping its args so `update_story_arg` can set them
+#[doc(hidden)]
+pub fn __render_reactive<T: ReactiveStory + crate::StoryMeta>(args: JsValue, container: &web_sys::Element) {
+    let component: T::StoryArgs = serde_wasm_bindgen::from_value(args)
+        .unwrap_or_else(|err| panic!("Invalid args for story '{}': {}", T::name(), err));
+    let args = ReactiveStoryArgs::new(component.into());
+    crate::STORY_INSTANCES.with(|instances| instances.borrow_mut().insert(T::name().to_string(), Box::new(args.clone())));
+    dominator::append_dom(container, T::to_reactive_story(args));
+}
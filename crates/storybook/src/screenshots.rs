@@ -0,0 +1,44 @@
+// Captures every story tagged by `#[story(screenshot)]`, using the story IDs from Storybook's index
+const CAPTURE_SCREENSHOTS_JS: &str = r#"// Generated by storybook::screenshots::generate_story_screenshots_script - do not edit
+//
+// Usage: STORYBOOK_URL=http://localhost:6006 SCREENSHOT_DIR=screenshots node capture-screenshots.js
+import { chromium } from 'playwright';
+import { mkdir } from 'node:fs/promises';
+import { join } from 'node:path';
+
+const baseUrl = (process.env.STORYBOOK_URL ?? 'http://localhost:6006').replace(/\/$/, '');
+const outputDir = process.env.SCREENSHOT_DIR ?? 'screenshots';
+
+const index = await fetch(`${baseUrl}/index.json`).then((response) => response.json());
+const stories = Object.values(index.entries).filter(
+  (entry) => entry.type === 'story' && entry.tags?.includes('screenshot'),
+);
+
+await mkdir(outputDir, { recursive: true });
+
+const browser = await chromium.launch();
+const page = await browser.newPage();
+
+for (const story of stories) {
+  await page.goto(`${baseUrl}/iframe.html?id=${story.id}&viewMode=story`);
+  await page.waitForSelector('#storybook-root > *');
+  const path = join(outputDir, `${story.id}.png`);
+  await page.screenshot({ path });
+  console.log(`${story.title} / ${story.name} -> ${path}`);
+}
+
+await browser.close();
+"#;
+
+/// Write a Playwright script that saves a PNG of every story with `#[story(screenshot)]`
+///
+/// The Storybook URL and output directory are read from the `STORYBOOK_URL` and
+/// `SCREENSHOT_DIR` environment variables when the script runs.
+///
+/// ```ignore
+/// // build.rs
+/// storybook::screenshots::generate_story_screenshots_script("capture-screenshots.js")?;
+/// ```
+pub fn generate_story_screenshots_script(path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+    std::fs::write(path, CAPTURE_SCREENSHOTS_JS)
+}
@@ -0,0 +1,130 @@
+use wasm_bindgen::prelude::*;
+use dominator::{Dom, html};
+use serde::{Deserialize, Serialize};
+
+use crate::{ArgType, Story, StoryMeta};
+
+thread_local! {
+    // Set while a thumbnail renders, so overview stories shown in one don't render thumbnails of
+    // their own, which could include the overview again and never finish
+    static IN_THUMBNAIL: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Overview page showing a thumbnail of every registered story, for design reviews
+///
+/// Register it like any other story and give it a `.stories.js` file that renders `StoryMap`:
+///
+/// ```ignore
+/// storybook::register_stories!(storybook::story_map::StoryMap, Button, Card);
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StoryMap {}
+
+impl StoryMap {
+    /// Create the story map; the stories are read from the registry when it renders
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StoryMeta for StoryMap {
+    type StoryArgs = StoryMap;
+
+    fn name() -> &'static str {
+        "StoryMap"
+    }
+
+    fn args() -> Vec<ArgType> {
+        Vec::new()
+    }
+}
+
+impl Story for StoryMap {
+    fn to_story(self) -> Dom {
+        if in_thumbnail() {
+            return overview_placeholder("Story map");
+        }
+        let stories = crate::collect_stories(|meta| (meta.name != Self::name()).then(|| (meta.name, meta.args.clone())));
+
+        html!("div", {
+            .style("display", "grid")
+            .style("grid-template-columns", "repeat(auto-fill, minmax(240px, 1fr))")
+            .style("gap", "24px")
+            .children(stories.into_iter().map(|(name, args)| thumbnail(name, args)))
+        })
+    }
+}
+
+// A story rendered at half size with its name below it
+fn thumbnail(name: &'static str, args: crate::ArgsFn) -> Dom {
+    html!("figure", {
+        .style("margin", "0")
+        .style("display", "flex")
+        .style("flex-direction", "column")
+        .style("gap", "8px")
+        .children(&mut [
+            html!("div", {
+                .style("height", "160px")
+                .style("overflow", "hidden")
+                .style("border", "1px solid #ddd")
+                .style("border-radius", "4px")
+                .child(html!("div", {
+                    .style("width", "200%")
+                    .style("transform", "scale(0.5)")
+                    .style("transform-origin", "top left")
+                    // Stories are mounted once the thumbnail is in the document, like in Storybook
+                    .after_inserted(move |container: web_sys::HtmlElement| {
+                        if let Err(err) = mount_with_default_args(name, &args(), &container) {
+                            let message = err.as_string().unwrap_or_else(|| format!("{:?}", err));
+                            dominator::append_dom(&container, crate::render_error(&format!("Story '{}' failed to render", name), &message));
+                        }
+                    })
+                }))
+            }),
+            html!("figcaption", {
+                .text(name)
+                .style("font-family", "sans-serif")
+                .style("font-size", "12px")
+                .style("color", "#666")
+            }),
+        ])
+    })
+}
+
+pub(crate) fn mount_with_default_args(name: &str, arg_types: &[ArgType], container: &web_sys::Element) -> Result<(), JsValue> {
+    let default_args: serde_json::Map<String, serde_json::Value> = arg_types
+        .iter()
+        .filter_map(|arg| Some((arg.name.clone(), default_arg_value(arg.default_value.as_deref()?))))
+        .collect();
+    let args = default_args.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?;
+    let outer = IN_THUMBNAIL.with(|in_thumbnail| in_thumbnail.replace(true));
+    let mounted = crate::mount_story_into(name, args, container);
+    IN_THUMBNAIL.with(|in_thumbnail| in_thumbnail.set(outer));
+    mounted
+}
+
+// Whether a story is being rendered into a `StoryMap` or `StoryGrid` thumbnail
+pub(crate) fn in_thumbnail() -> bool {
+    IN_THUMBNAIL.with(|in_thumbnail| in_thumbnail.get())
+}
+
+// Shown instead of an overview story's thumbnails when it is itself in a thumbnail
+pub(crate) fn overview_placeholder(title: &str) -> Dom {
+    html!("div", {
+        .style("font-family", "sans-serif")
+        .style("color", "#666")
+        .style("padding", "16px")
+        .text(title)
+    })
+}
+
+// Defaults are JS literals such as `42` or `'#007bff'`, or plain text like lorem ipsum and enum variants
+pub(crate) fn default_arg_value(default_value: &str) -> serde_json::Value {
+    if let Ok(value) = serde_json::from_str(default_value) {
+        return value;
+    }
+    match default_value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')) {
+        Some(quoted) => serde_json::Value::from(quoted.replace("\\'", "'")),
+        None => serde_json::Value::from(default_value),
+    }
+}
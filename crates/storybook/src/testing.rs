@@ -0,0 +1,98 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+
+use crate::{ArgType, ControlType};
+
+/// Generate random args for a story from its arg types, for property-based tests
+///
+/// Numbers are whole and stay within `min`/`max`, text is at least `min_length` characters, and
+/// selects pick one of their options. Optional args are sometimes `null`, and args with other
+/// controls keep their default. The same seed always gives the same args. Panics if no story
+/// with the given name is registered.
+///
+/// ```ignore
+/// register_all_stories();
+/// for args in storybook::testing::story_args_fuzzer("Button", 42).take(100) {
+///     render_story("Button", args).expect("valid args should render");
+/// }
+/// ```
+pub fn story_args_fuzzer(story_name: &str, seed: u64) -> impl Iterator<Item = JsValue> {
+    let args_fn = crate::story_args_fn(story_name)
+        .unwrap_or_else(|| panic!("Story '{}' not found", story_name));
+
+    let arg_types = args_fn();
+    let mut rng = SplitMix64(seed);
+    std::iter::from_fn(move || {
+        let args: serde_json::Map<String, serde_json::Value> = arg_types
+            .iter()
+            .filter_map(|arg| Some((arg.name.clone(), random_arg_value(arg, &mut rng)?)))
+            .collect();
+        Some(
+            args.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .expect("JSON args always convert to JS"),
+        )
+    })
+}
+
+// A random value fitting the arg's control, or `None` to leave the arg out
+fn random_arg_value(arg: &ArgType, rng: &mut SplitMix64) -> Option<serde_json::Value> {
+    if !arg.required && rng.below(4) == 0 {
+        return Some(serde_json::Value::Null);
+    }
+
+    match arg.control {
+        ControlType::Boolean | ControlType::Switch => Some(serde_json::Value::Bool(rng.below(2) == 0)),
+        ControlType::Number | ControlType::Range => {
+            // Whole numbers, so integer fields deserialize too
+            let min = arg.min.unwrap_or_else(|| arg.max.map_or(-100.0, |max| max - 200.0)).ceil();
+            let max = arg.max.unwrap_or(min + 200.0).floor();
+            if min > max {
+                return Some(serde_json::Value::from(min));
+            }
+            // Ranges wider than a u64 are drawn from the whole u64 range instead
+            let offset = match ((max - min) as u64).checked_add(1) {
+                Some(span) => rng.below(span),
+                None => rng.next(),
+            };
+            Some(serde_json::Value::from((min + offset as f64).min(max)))
+        }
+        ControlType::Text => {
+            let min_length = arg.min_length.unwrap_or(0);
+            let length = min_length + rng.below(17) as usize;
+            let text: String = (0..length)
+                .map(|_| {
+                    const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 ";
+                    CHARS[rng.below(CHARS.len() as u64) as usize] as char
+                })
+                .collect();
+            Some(serde_json::Value::from(text))
+        }
+        ControlType::Select | ControlType::InlineRadio => {
+            let options = arg.options.as_ref().filter(|options| !options.is_empty())?;
+            let option = &options[rng.below(options.len() as u64) as usize];
+            Some(serde_json::Value::from(option.as_str()))
+        }
+        ControlType::Color => Some(serde_json::Value::from(format!("#{:06x}", rng.below(0x100_0000)))),
+        ControlType::Object | ControlType::Inline | ControlType::Custom => {
+            arg.default_json()
+        }
+    }
+}
+
+// Small seedable PRNG, so fuzzed args are reproducible without pulling in `rand`
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform-enough value in `0..bound`; `bound` must not be zero
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
@@ -0,0 +1,309 @@
+use dominator::{Dom, html};
+
+/// Design tokens found in CSS custom properties, as `(name, value)` grouped by kind
+///
+/// Names keep their `--` prefix, e.g. `("--color-primary", "#007bff")`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenMap {
+    pub colors: Vec<(String, String)>,
+    pub spacing: Vec<(String, String)>,
+    pub typography: Vec<(String, String)>,
+    pub radii: Vec<(String, String)>,
+    pub shadows: Vec<(String, String)>,
+    /// Tokens that don't fit any other group
+    pub other: Vec<(String, String)>,
+}
+
+/// Collect the custom properties declared in a stylesheet, e.g. `:root { --spacing-sm: 8px; }`
+///
+/// Tokens are grouped by their name (`--color-*`, `--spacing-*`, `--font-*`, `--radius-*`,
+/// `--shadow-*`), and by their value for colors named otherwise, e.g. `--brand: #007bff`.
+/// A token declared twice keeps its last value, like in CSS. This is a scanner for the
+/// declarations, not a CSS parser, so values containing `;` or braces aren't supported.
+///
+/// ```ignore
+/// let tokens = extract_design_tokens(include_str!("../styles/tokens.css"));
+/// assert_eq!(tokens.colors[0], ("--color-primary".to_string(), "#007bff".to_string()));
+/// ```
+pub fn extract_design_tokens(css_content: &str) -> TokenMap {
+    let mut declarations: Vec<(String, String)> = Vec::new();
+    for declaration in strip_comments(css_content).split(['{', '}', ';']) {
+        let Some((name, value)) = declaration.split_once(':') else {
+            continue;
+        };
+        let (name, value) = (name.trim(), value.trim());
+        if !name.starts_with("--") || value.is_empty() {
+            continue;
+        }
+
+        match declarations.iter_mut().find(|(declared, _)| declared == name) {
+            Some((_, declared_value)) => *declared_value = value.to_string(),
+            None => declarations.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    let mut tokens = TokenMap::default();
+    for (name, value) in declarations {
+        let group = match token_kind(&name, &value) {
+            TokenKind::Color => &mut tokens.colors,
+            TokenKind::Spacing => &mut tokens.spacing,
+            TokenKind::Typography => &mut tokens.typography,
+            TokenKind::Radius => &mut tokens.radii,
+            TokenKind::Shadow => &mut tokens.shadows,
+            TokenKind::Other => &mut tokens.other,
+        };
+        group.push((name, value));
+    }
+    tokens
+}
+
+impl TokenMap {
+    pub fn is_empty(&self) -> bool {
+        self.groups().all(|(_, tokens)| tokens.is_empty())
+    }
+
+    /// Document the tokens, for a `DesignTokens` story
+    ///
+    /// Colors are shown as swatches, spacing as bars of that width, radii and shadows on a
+    /// sample box, and other tokens as their value.
+    ///
+    /// ```ignore
+    /// #[derive(Story, Deserialize)]
+    /// struct DesignTokens {}
+    ///
+    /// impl Story for DesignTokens {
+    ///     fn to_story(self) -> Dom {
+    ///         extract_design_tokens(include_str!("../styles/tokens.css")).render()
+    ///     }
+    /// }
+    /// ```
+    pub fn render(&self) -> Dom {
+        html!("div", {
+            .style("font-family", "sans-serif")
+            .style("display", "flex")
+            .style("flex-direction", "column")
+            .style("gap", "24px")
+            .children(self.groups().filter(|(_, tokens)| !tokens.is_empty()).map(|(kind, tokens)| token_group(kind, tokens)))
+        })
+    }
+
+    fn groups(&self) -> impl Iterator<Item = (TokenKind, &[(String, String)])> {
+        [
+            (TokenKind::Color, self.colors.as_slice()),
+            (TokenKind::Spacing, self.spacing.as_slice()),
+            (TokenKind::Typography, self.typography.as_slice()),
+            (TokenKind::Radius, self.radii.as_slice()),
+            (TokenKind::Shadow, self.shadows.as_slice()),
+            (TokenKind::Other, self.other.as_slice()),
+        ]
+        .into_iter()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TokenKind {
+    Color,
+    Spacing,
+    Typography,
+    Radius,
+    Shadow,
+    Other,
+}
+
+impl TokenKind {
+    fn title(self) -> &'static str {
+        match self {
+            TokenKind::Color => "Colors",
+            TokenKind::Spacing => "Spacing",
+            TokenKind::Typography => "Typography",
+            TokenKind::Radius => "Radii",
+            TokenKind::Shadow => "Shadows",
+            TokenKind::Other => "Other",
+        }
+    }
+}
+
+// Group a token by the words in its name, falling back to its value for colors
+fn token_kind(name: &str, value: &str) -> TokenKind {
+    let name = name.to_ascii_lowercase();
+    if name.contains("color") || name.contains("colour") {
+        TokenKind::Color
+    } else if name.contains("font") || name.contains("line-height") || name.contains("letter-spacing") {
+        // Before spacing, which `letter-spacing` would otherwise match
+        TokenKind::Typography
+    } else if name.contains("spacing") || name.contains("space") || name.contains("gap") {
+        TokenKind::Spacing
+    } else if name.contains("radius") || name.contains("radii") {
+        TokenKind::Radius
+    } else if name.contains("shadow") {
+        TokenKind::Shadow
+    } else if is_color_value(value) {
+        TokenKind::Color
+    } else {
+        TokenKind::Other
+    }
+}
+
+fn is_color_value(value: &str) -> bool {
+    let value = value.to_ascii_lowercase();
+    value.starts_with('#') || ["rgb(", "rgba(", "hsl(", "hsla(", "oklch(", "color-mix("].iter().any(|prefix| value.starts_with(prefix))
+}
+
+// The property a typography token sets, e.g. `font-size` for `--font-size-lg`
+fn typography_property(name: &str) -> &'static str {
+    let name = name.to_ascii_lowercase();
+    if name.contains("line-height") {
+        "line-height"
+    } else if name.contains("letter-spacing") {
+        "letter-spacing"
+    } else if name.contains("weight") {
+        "font-weight"
+    } else if name.contains("size") {
+        "font-size"
+    } else {
+        "font-family"
+    }
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut stripped = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        rest = rest[start + 2..].find("*/").map_or("", |end| &rest[start + 2 + end + 2..]);
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+// One group of tokens under its heading
+fn token_group(kind: TokenKind, tokens: &[(String, String)]) -> Dom {
+    html!("section", {
+        .children(&mut [
+            html!("h3", {
+                .style("margin", "0 0 8px")
+                .text(kind.title())
+            }),
+            html!("div", {
+                .style("display", "flex")
+                .style("flex-direction", "column")
+                .style("gap", "8px")
+                .children(tokens.iter().map(|(name, value)| token_row(kind, name, value)))
+            }),
+        ])
+    })
+}
+
+// A token's preview next to its name and value
+//
+// Token values are set unchecked, as `style` panics on values the browser rejects
+fn token_row(kind: TokenKind, name: &str, value: &str) -> Dom {
+    let preview = match kind {
+        TokenKind::Color => html!("div", {
+            .style("width", "32px")
+            .style("height", "32px")
+            .style("border", "1px solid #ddd")
+            .style("border-radius", "4px")
+            .style_unchecked("background", value)
+        }),
+        TokenKind::Spacing => html!("div", {
+            .style_unchecked("width", value)
+            .style("height", "8px")
+            .style("background", "#1ea7fd")
+        }),
+        TokenKind::Typography => html!("span", {
+            .style_unchecked(typography_property(name), value)
+            .text("Aa")
+        }),
+        TokenKind::Radius => html!("div", {
+            .style("width", "32px")
+            .style("height", "32px")
+            .style("border", "1px solid #999")
+            .style_unchecked("border-radius", value)
+        }),
+        TokenKind::Shadow => html!("div", {
+            .style("width", "32px")
+            .style("height", "32px")
+            .style("background", "white")
+            .style_unchecked("box-shadow", value)
+        }),
+        TokenKind::Other => html!("span", {}),
+    };
+
+    html!("div", {
+        .style("display", "flex")
+        .style("align-items", "center")
+        .style("gap", "12px")
+        .children(&mut [
+            preview,
+            html!("code", {
+                .text(name)
+            }),
+            html!("span", {
+                .style("color", "#666")
+                .text(value)
+            }),
+        ])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(name: &str, value: &str) -> (String, String) {
+        (name.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn groups_tokens_by_name() {
+        let tokens = extract_design_tokens(
+            ":root {
+                --color-primary: #007bff;
+                --spacing-sm: 8px;
+                --font-size-lg: 20px;
+                --radius-md: 4px;
+                --shadow-card: 0 1px 2px black;
+                --z-modal: 100;
+            }",
+        );
+        assert_eq!(tokens.colors, vec![token("--color-primary", "#007bff")]);
+        assert_eq!(tokens.spacing, vec![token("--spacing-sm", "8px")]);
+        assert_eq!(tokens.typography, vec![token("--font-size-lg", "20px")]);
+        assert_eq!(tokens.radii, vec![token("--radius-md", "4px")]);
+        assert_eq!(tokens.shadows, vec![token("--shadow-card", "0 1px 2px black")]);
+        assert_eq!(tokens.other, vec![token("--z-modal", "100")]);
+    }
+
+    #[test]
+    fn letter_spacing_and_line_height_are_typography() {
+        let tokens = extract_design_tokens(":root { --letter-spacing-wide: 0.1em; --line-height-tight: 1.2; --space-lg: 24px; }");
+        assert_eq!(
+            tokens.typography,
+            vec![token("--letter-spacing-wide", "0.1em"), token("--line-height-tight", "1.2")]
+        );
+        assert_eq!(tokens.spacing, vec![token("--space-lg", "24px")]);
+    }
+
+    #[test]
+    fn colors_are_recognized_by_value() {
+        let tokens = extract_design_tokens(":root { --brand: rgb(0, 123, 255); --accent: #ff0; }");
+        assert_eq!(tokens.colors, vec![token("--brand", "rgb(0, 123, 255)"), token("--accent", "#ff0")]);
+    }
+
+    #[test]
+    fn later_declarations_win_and_comments_are_ignored() {
+        let tokens = extract_design_tokens(
+            "/* --color-hidden: red; */
+            :root { --color-primary: #000; }
+            .dark { --color-primary: #fff; color: var(--color-primary); }",
+        );
+        assert_eq!(tokens.colors, vec![token("--color-primary", "#fff")]);
+        assert!(tokens.other.is_empty());
+    }
+
+    #[test]
+    fn empty_stylesheet_has_no_tokens() {
+        assert!(extract_design_tokens("body { margin: 0; }").is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+
+/// Helper for testing stories with `wasm_bindgen_test`
+///
+/// ```ignore
+/// #[wasm_bindgen_test]
+/// fn button_renders() {
+///     register_all_stories();
+///     WasmStoryTest::for_story("Button")
+///         .with_arg("color", "#ff0000")
+///         .assert_rendered(|node| {
+///             assert!(node.text_content().unwrap().contains("Clicked"));
+///         });
+/// }
+/// ```
+pub struct WasmStoryTest {
+    story_name: String,
+    args: serde_json::Map<String, serde_json::Value>,
+}
+
+impl WasmStoryTest {
+    /// Start a test for the registered story with the given name
+    pub fn for_story(story_name: &str) -> Self {
+        Self {
+            story_name: story_name.to_string(),
+            args: serde_json::Map::new(),
+        }
+    }
+
+    /// Set an arg passed to the story when it is rendered
+    pub fn with_arg(mut self, name: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.args.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Render the story, returning the container element it was mounted into
+    pub fn render(&self) -> Result<web_sys::Element, JsValue> {
+        let args = self
+            .args
+            .serialize(&serde_wasm_bindgen::Serializer::json_compatible())?;
+        crate::render_story_container(&self.story_name, args)
+    }
+
+    /// Render the story and run DOM assertions against its container element
+    ///
+    /// Panics if the story can't be rendered.
+    pub fn assert_rendered(self, assertions: impl FnOnce(&web_sys::Element)) {
+        match self.render() {
+            Ok(element) => assertions(&element),
+            Err(err) => panic!("Failed to render story '{}': {:?}", self.story_name, err),
+        }
+    }
+}
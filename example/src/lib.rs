@@ -2,6 +2,9 @@ use dominator::{html, Dom};
 use futures_signals::signal::{Mutable, SignalExt};
 use serde::Deserialize;
 use std::sync::Arc;
+use wasm_bindgen::JsValue;
+use storybook::accessibility::AriaMetadata;
+use storybook::event_log::EventLog;
 use storybook::Story;
 use storybook::{StoryDerive, StorySelect};
 
@@ -26,14 +29,17 @@ impl ButtonSize {
 }
 
 /// A simple button component with auto-registration
-#[derive(StoryDerive, Deserialize)]
+#[derive(StoryDerive, AriaMetadata, Deserialize)]
+#[aria(role = "button")]
+#[story(aria_label = "Counter button", generate_play_fn = "interactions")]
 pub struct Button {
-    #[story(from = "usize", default = "0")]
+    #[story(from = "usize", default = "0", min = "0", max = "100")]
     pub count: Mutable<usize>,
     #[story(control = "color", default = "'#007bff'")]
     pub color: String,
     #[story(control = "select")]
     pub size: ButtonSize,
+    #[story(control = "switch")]
     pub disabled: Option<bool>,
     #[story(skip)]
     #[serde(skip)]
@@ -45,11 +51,13 @@ impl Story for Button {
     fn to_story(self) -> Dom {
         let is_disabled = self.disabled.unwrap_or(false);
         html!("button", {
+            .attr("aria-label", "Counter button")
             .text_signal(self.count.signal().map(|n| format!("Clicked {n} times")))
             .event({
                 let count = self.count.clone();
                 move |_: dominator::events::Click| {
                     count.replace_with(|x| *x + 1);
+                    EventLog::push("click", JsValue::NULL);
                 }
             })
             .style("background-color", &self.color)
@@ -67,17 +75,22 @@ impl Story for Button {
 
 /// A simple card component with auto-registration
 #[derive(StoryDerive, Deserialize)]
+#[story(width = "400px", live_controls)]
 pub struct Card {
     #[story(lorem = "3")]
     pub title: String,
-    #[story(lorem)]
+    #[story(lorem, rows = "4", resize = "vertical")]
     pub content: String,
     #[story(control = "color", default = "'#fcfcfc`'")]
     pub background: String,
+    #[story(control = "range", default = "1")]
+    pub opacity: Mutable<f32>,
 }
 
 impl Story for Card {
     fn to_story(self) -> Dom {
+        storybook::story_invariant!(!self.title.is_empty(), "Card title must not be empty");
+
         html!("div", {
             .style("background-color", &self.background)
             .style("border", "1px solid #ddd")
@@ -85,6 +98,7 @@ impl Story for Card {
             .style("padding", "20px")
             .style("box-shadow", "0 2px 4px rgba(0,0,0,0.1)")
             .style("max-width", "400px")
+            .style_signal("opacity", self.opacity.signal().map(|opacity| opacity.to_string()))
             .children(&mut [
                 html!("h2", {
                     .text(&self.title)
@@ -104,10 +118,12 @@ impl Story for Card {
 /// A simple text input component with auto-registration
 #[derive(StoryDerive, Deserialize)]
 pub struct Input {
-    #[story(lorem = "2")]
+    #[story(lorem = "2", i18n_key = "input.placeholder")]
     pub placeholder: String,
     #[story(lorem = "4")]
     pub value: String,
+    #[story(control = "select", true_label = "Read only", false_label = "Editable")]
+    pub readonly: bool,
 }
 
 impl Story for Input {
@@ -116,6 +132,7 @@ impl Story for Input {
             .attr("type", "text")
             .attr("placeholder", &self.placeholder)
             .attr("value", &self.value)
+            .prop("readOnly", self.readonly)
             .style("padding", "10px")
             .style("border", "1px solid #ccc")
             .style("border-radius", "4px")
@@ -126,9 +143,9 @@ impl Story for Input {
 }
 
 /// Alert severity levels
-#[derive(StorySelect, Deserialize, Clone, Debug, Default)]
+#[derive(StorySelect, Deserialize, Clone, Debug)]
+#[story(derive_default_from_first_variant)]
 pub enum AlertType {
-    #[default]
     Info,
     Success,
     Warning,
@@ -148,6 +165,8 @@ impl AlertType {
 
 /// An alert component demonstrating enum select controls
 #[derive(StoryDerive, Deserialize)]
+#[story(responsive_preview, note = r#"## Usage
+Pick a severity with the `alert_type` control."#)]
 pub struct Alert {
     #[story(lorem = "5")]
     pub message: String,
@@ -169,6 +188,28 @@ impl Story for Alert {
     }
 }
 
+/// A JSON preview component demonstrating object controls
+#[derive(StoryDerive, Deserialize)]
+#[story(live_preview, feature_flag = "experimental")]
+pub struct JsonViewer {
+    #[story(control = "json", default = "{ name: 'storybook', tags: ['rust', 'wasm'] }")]
+    pub data: serde_json::Value,
+}
+
+impl Story for JsonViewer {
+    fn to_story(self) -> Dom {
+        let formatted = serde_json::to_string_pretty(&self.data).unwrap_or_default();
+        html!("pre", {
+            .text(&formatted)
+            .style("padding", "15px")
+            .style("background-color", "#f5f5f5")
+            .style("border-radius", "4px")
+            .style("font-family", "monospace")
+        })
+    }
+}
+
 // Automatically generate registration function using macro
-storybook::register_stories!(Button, Card, Input, Alert);
+storybook::register_stories!(Button, Card, Input, Alert, JsonViewer);
 storybook::register_enums!(AlertType, ButtonSize);
+storybook::register_aria!(Button);
@@ -1,2 +1,2 @@
 #! /usr/bin/env bash
-cargo +nightly publish -p storybook -p storybook-derive
\ No newline at end of file
+cargo +nightly publish -p storybook-template -p storybook -p storybook-derive
\ No newline at end of file